@@ -0,0 +1,173 @@
+// 成就：游戏本体把每局发生的事情喂给 RunProgress，满足条件的成就永久解锁
+// 解锁记录保存在玩家档案中（见 profile.rs）
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Goal {
+    ClearCacti(u32), // 一局内跳过的仙人掌数量
+    Survive(f32),    // 一局内坚持的秒数
+    CleanJumps(u32), // 连续多少次起跳都只跳一下就越过了仙人掌
+    ReachNight,      // 跑到黑夜
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Achievement {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    goal: Goal,
+}
+
+pub const ACHIEVEMENTS: [Achievement; 6] = [
+    Achievement {
+        id: "first_cactus",
+        title: "First Steps",
+        description: "Clear your first cactus",
+        goal: Goal::ClearCacti(1),
+    },
+    Achievement {
+        id: "cactus_50",
+        title: "Cactus Crusher",
+        description: "Clear 50 cacti in one run",
+        goal: Goal::ClearCacti(50),
+    },
+    Achievement {
+        id: "survive_60",
+        title: "Warming Up",
+        description: "Survive 1 minute",
+        goal: Goal::Survive(60.0),
+    },
+    Achievement {
+        id: "survive_120",
+        title: "Marathon",
+        description: "Survive 2 minutes",
+        goal: Goal::Survive(120.0),
+    },
+    Achievement {
+        id: "clean_jumps_10",
+        title: "Light Feet",
+        description: "10 jumps in a row that clear a cactus without a double jump",
+        goal: Goal::CleanJumps(10),
+    },
+    Achievement {
+        id: "night",
+        title: "Night Owl",
+        description: "Run until night falls",
+        goal: Goal::ReachNight,
+    },
+];
+
+pub fn find(id: &str) -> Option<&'static Achievement> {
+    ACHIEVEMENTS.iter().find(|achievement| achievement.id == id)
+}
+
+// 一局的进度，开局时重置
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunProgress {
+    pub cacti: u32,
+    pub time: f32,
+    pub clean_jumps: u32,
+    pub night: bool,
+    airborne: Option<(bool, u32)>, // 本次跳跃是否用了二段跳，以及跳过的仙人掌数
+}
+
+impl RunProgress {
+    pub fn advance(&mut self, dt: f32) {
+        self.time += dt;
+    }
+
+    // air_jump 表示在空中用二段跳再跳了一次，这次跳跃就不算干净的一跳
+    pub fn jumped(&mut self, air_jump: bool) {
+        match &mut self.airborne {
+            Some((air_jumped, _)) => *air_jumped |= air_jump,
+            None => self.airborne = Some((air_jump, 0)),
+        }
+    }
+
+    // 落地时结算这次跳跃：跳过了仙人掌且没用二段跳才计入连续次数，否则从零开始
+    pub fn landed(&mut self) {
+        let Some((air_jumped, cleared)) = self.airborne.take() else {
+            return;
+        };
+        if cleared > 0 && !air_jumped {
+            self.clean_jumps += 1;
+        } else {
+            self.clean_jumps = 0;
+        }
+    }
+
+    pub fn cleared(&mut self) {
+        self.cacti += 1;
+        if let Some((_, cleared)) = &mut self.airborne {
+            *cleared += 1;
+        }
+    }
+
+    pub fn reached_night(&mut self) {
+        self.night = true;
+    }
+
+    fn reached(&self, goal: Goal) -> bool {
+        match goal {
+            Goal::ClearCacti(count) => self.cacti >= count,
+            Goal::Survive(secs) => self.time >= secs,
+            Goal::CleanJumps(count) => self.clean_jumps >= count,
+            Goal::ReachNight => self.night,
+        }
+    }
+
+    // 本局已经满足条件的成就
+    pub fn completed(&self) -> impl Iterator<Item = &'static Achievement> + '_ {
+        ACHIEVEMENTS.iter().filter(|achievement| self.reached(achievement.goal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jump(progress: &mut RunProgress, air_jump: bool, cacti: u32) {
+        progress.jumped(false);
+        if air_jump {
+            progress.jumped(true);
+        }
+        for _ in 0..cacti {
+            progress.cleared();
+        }
+        progress.landed();
+    }
+
+    #[test]
+    fn clean_jumps_count_single_jumps_that_clear_a_cactus() {
+        let mut progress = RunProgress::default();
+        jump(&mut progress, false, 1);
+        jump(&mut progress, false, 2);
+        assert_eq!(progress.clean_jumps, 2);
+        // 用了二段跳或什么都没跳过都会从零开始
+        jump(&mut progress, true, 1);
+        assert_eq!(progress.clean_jumps, 0);
+        jump(&mut progress, false, 1);
+        jump(&mut progress, false, 0);
+        assert_eq!(progress.clean_jumps, 0);
+        assert_eq!(progress.cacti, 5);
+    }
+
+    #[test]
+    fn completed_reports_reached_goals() {
+        let mut progress = RunProgress::default();
+        assert_eq!(progress.completed().count(), 0);
+        for _ in 0..10 {
+            jump(&mut progress, false, 1);
+        }
+        progress.advance(61.0);
+        let ids: Vec<&str> = progress.completed().map(|achievement| achievement.id).collect();
+        assert_eq!(ids, ["first_cactus", "survive_60", "clean_jumps_10"]);
+    }
+
+    #[test]
+    fn ids_are_unique() {
+        for (index, achievement) in ACHIEVEMENTS.iter().enumerate() {
+            assert!(ACHIEVEMENTS[index + 1..].iter().all(|other| other.id != achievement.id));
+            assert_eq!(find(achievement.id), Some(achievement));
+        }
+    }
+}
@@ -0,0 +1,215 @@
+// 成就：跟踪本地单人局的进度，解锁时在屏幕上方弹出提示，菜单里按 A 查看全部成就
+// 解锁记录随玩家档案保存在 saves/profile.txt
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use dino_game::achievement::{Achievement, RunProgress, ACHIEVEMENTS};
+use dino_game::daily::Date;
+use dino_game::profile::Profile;
+
+use crate::daynight::{self, DayNight};
+use crate::events::{Crashed, Jumped, Landed, ObstacleCleared};
+use crate::menu::MenuPage;
+use crate::skin::UserSettings;
+use crate::{GameState, Lane, LocalPlayers};
+
+const TOAST_SECS: f32 = 3.0;
+// 单人局的玩家在 0 号跑道
+pub const PLAYER: Lane = Lane(0);
+
+#[derive(Resource)]
+pub struct PlayerProfile(pub Profile);
+
+// 本局的成就进度；玩家最后一条命用完后停止计时
+#[derive(Resource, Default)]
+struct RunTracker {
+    progress: RunProgress,
+    finished: bool,
+}
+
+// 等待弹出的解锁提示，一次显示一条
+#[derive(Resource, Default)]
+struct Toasts {
+    queue: VecDeque<&'static Achievement>,
+    remaining: f32,
+}
+
+#[derive(Component)]
+struct ToastText;
+
+#[derive(Component)]
+struct AchievementsText;
+
+pub struct AchievementPlugin;
+
+impl Plugin for AchievementPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PlayerProfile(Profile::load()))
+            .init_resource::<RunTracker>()
+            .init_resource::<Toasts>()
+            .add_systems(OnEnter(GameState::Playing), reset_tracker)
+            .add_systems(
+                Update,
+                track_run.after(crate::update_score).run_if(in_state(GameState::Playing).and(solo_run)),
+            )
+            .add_systems(Update, show_toasts)
+            .add_systems(
+                Update,
+                (toggle_achievements, draw_achievements).chain().run_if(in_state(GameState::Menu)),
+            )
+            .add_systems(OnExit(GameState::Menu), despawn_achievements);
+    }
+}
+
+fn reset_tracker(mut tracker: ResMut<RunTracker>) {
+    *tracker = RunTracker::default();
+}
+
+// 成就和生涯统计只记录本地单人局里由玩家自己操作的恐龙；联机、双人和 AI 代打都不算
+pub fn solo_run(
+    players: Res<LocalPlayers>,
+    net_session: Option<Res<crate::netplay::NetSession>>,
+    ai: Option<Res<crate::ai::AiPlayer>>,
+) -> bool {
+    players.count == 1 && net_session.is_none() && ai.is_none()
+}
+
+fn track_run(
+    time: Res<Time>,
+    day_night: Res<DayNight>,
+    settings: Res<UserSettings>,
+    mut jumped: EventReader<Jumped>,
+    mut cleared: EventReader<ObstacleCleared>,
+    mut landed: EventReader<Landed>,
+    mut crashes: EventReader<Crashed>,
+    mut tracker: ResMut<RunTracker>,
+    mut profile: ResMut<PlayerProfile>,
+    mut toasts: ResMut<Toasts>,
+) {
+    if tracker.finished {
+        return;
+    }
+
+    // 同一帧内按起跳、跳过、落地的顺序结算，与各系统的运行顺序一致
+    let tracker = &mut *tracker;
+    tracker.progress.advance(time.delta_secs());
+    for event in jumped.read().filter(|event| event.lane == PLAYER) {
+        tracker.progress.jumped(event.air_jump);
+    }
+    for _ in cleared.read().filter(|event| event.lane == PLAYER) {
+        tracker.progress.cleared();
+    }
+    for _ in landed.read().filter(|event| event.lane == PLAYER) {
+        tracker.progress.landed();
+    }
+    if day_night.is_night() {
+        tracker.progress.reached_night();
+    }
+    tracker.finished = crashes.read().any(|crash| crash.lane == PLAYER && crash.lives_left == 0);
+
+    let mut unlocked = false;
+    for achievement in tracker.progress.completed() {
+        // 已经解锁过的成就每帧都会出现在这里，只有新解锁时才取当天日期
+        if profile.0.unlocked(achievement.id).is_some() {
+            continue;
+        }
+        profile.0.unlock(achievement.id, Date::today(settings.0.utc_offset));
+        toasts.queue.push_back(achievement);
+        unlocked = true;
+    }
+    if unlocked {
+        if let Err(e) = profile.0.save() {
+            warn!("保存玩家档案失败: {}", e);
+        }
+    }
+}
+
+// 在任何界面都继续倒计时，撞车前刚解锁的提示在游戏结束画面上也能看完
+fn show_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut toasts: ResMut<Toasts>,
+    query: Query<Entity, With<ToastText>>,
+) {
+    if toasts.remaining > 0.0 {
+        toasts.remaining -= time.delta_secs();
+        if toasts.remaining > 0.0 {
+            return;
+        }
+        for entity in query.iter() {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    let Some(achievement) = toasts.queue.pop_front() else {
+        return;
+    };
+    toasts.remaining = TOAST_SECS;
+    commands.spawn((
+        ToastText,
+        daynight::HudText,
+        Text2d::new(format!("Achievement unlocked: {}\n{}", achievement.title, achievement.description)),
+        Transform::from_xyz(0.0, 130.0, 2.0),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+    ));
+}
+
+// 按 A 打开成就页面，再按 A 返回主菜单
+fn toggle_achievements(keyboard_input: Res<ButtonInput<KeyCode>>, mut page: ResMut<MenuPage>) {
+    if keyboard_input.just_pressed(KeyCode::KeyA) {
+        *page = if *page == MenuPage::Achievements { MenuPage::Main } else { MenuPage::Achievements };
+    }
+}
+
+fn draw_achievements(
+    mut commands: Commands,
+    page: Res<MenuPage>,
+    profile: Res<PlayerProfile>,
+    mut query: Query<(Entity, &mut Text2d), With<AchievementsText>>,
+) {
+    if *page != MenuPage::Achievements {
+        for (entity, _) in query.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    let mut text = format!("ACHIEVEMENTS  {}/{}\n\n", profile.0.unlocked_count(), ACHIEVEMENTS.len());
+    for achievement in &ACHIEVEMENTS {
+        match profile.0.unlocked(achievement.id) {
+            Some(date) => text.push_str(&format!("[x] {} - {}  ({})\n", achievement.title, achievement.description, date)),
+            None => text.push_str(&format!("[ ] {} - {}\n", achievement.title, achievement.description)),
+        }
+    }
+    text.push_str("\nA/ESC: back");
+
+    match query.single_mut() {
+        Ok((_, mut page_text)) => {
+            if page_text.0 != text {
+                page_text.0 = text;
+            }
+        }
+        Err(_) => {
+            commands.spawn((
+                AchievementsText,
+                daynight::HudText,
+                Text2d::new(text),
+                Transform::from_xyz(0.0, 20.0, 1.0),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+            ));
+        }
+    }
+}
+
+fn despawn_achievements(mut commands: Commands, query: Query<Entity, With<AchievementsText>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,42 @@
+// AI 玩家：加载训练好的基因组，代替键盘决定何时起跳
+
+use bevy::prelude::*;
+use dino_game::neuro::Genome;
+use dino_game::sim::{sensors, GROUND_Y};
+
+use crate::ghost::Ghost;
+use crate::{InputState, Lane, LocalPlayers, Obstacle, Player, WorldSpeed};
+
+#[derive(Resource)]
+pub struct AiPlayer {
+    pub genome: Genome,
+    pub lane: Lane, // 由 AI 控制的玩家
+}
+
+// 在 handle_input 之后、player_input 之前运行，用网络的输出覆盖跳跃输入
+pub fn ai_player_input(
+    ai: Res<AiPlayer>,
+    players: Res<LocalPlayers>,
+    speed: Res<WorldSpeed>,
+    mut input_state: ResMut<InputState>,
+    player_query: Query<(&Player, &Transform, &Lane), Without<Ghost>>,
+    obstacle_query: Query<(&Transform, &Sprite, &Lane), With<Obstacle>>,
+) {
+    let Some((player, transform, _)) = player_query.iter().find(|(_, _, lane)| **lane == ai.lane) else {
+        return;
+    };
+
+    let obstacles = obstacle_query
+        .iter()
+        .filter(|(_, _, lane)| **lane == ai.lane)
+        .map(|(obstacle_transform, sprite, _)| {
+            let size = sprite.custom_size.unwrap_or(Vec2::ZERO);
+            (obstacle_transform.translation.x, size.x, size.y)
+        });
+    // 换算成单人跑道的高度，与训练时的模拟一致
+    let lane_offset = players.ground_y(ai.lane) - GROUND_Y;
+    let inputs = sensors(transform.translation.y - lane_offset, player.velocity_y, speed.0, obstacles);
+
+    // 与训练时的模拟一致：网络输出为正且恐龙在地面上就起跳
+    input_state.players[ai.lane.0].just_pressed = ai.genome.wants_jump(&inputs);
+}
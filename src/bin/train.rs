@@ -0,0 +1,145 @@
+// 神经进化训练器：在多个线程中并行运行无界面模拟，保存最好的基因组
+//
+// 用法：cargo run --release --bin train -- [--generations N] [--population N]
+//       [--episodes N] [--max-time 秒] [--seed N] [--out 文件] [--resume 文件]
+
+use std::path::PathBuf;
+use std::thread;
+
+use dino_game::neuro::{run_episode, EvolutionConfig, Genome, Population};
+
+struct TrainOptions {
+    generations: u32,
+    episodes: u32,
+    max_time: f32,
+    seed: u64,
+    out: PathBuf,
+    resume: Option<PathBuf>,
+    config: EvolutionConfig,
+}
+
+fn parse_args() -> Result<TrainOptions, String> {
+    let mut options = TrainOptions {
+        generations: 50,
+        episodes: 3,
+        max_time: 120.0,
+        seed: 42,
+        out: PathBuf::from("best_genome.txt"),
+        resume: None,
+        config: EvolutionConfig::default(),
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} 缺少参数值", arg));
+        match arg.as_str() {
+            "--generations" => options.generations = parse(&value()?)?,
+            "--population" => options.config.population = parse(&value()?)?,
+            "--episodes" => options.episodes = parse(&value()?)?,
+            "--max-time" => options.max_time = parse(&value()?)?,
+            "--seed" => options.seed = parse(&value()?)?,
+            "--out" => options.out = PathBuf::from(value()?),
+            "--resume" => options.resume = Some(PathBuf::from(value()?)),
+            _ => return Err(format!("未知参数: {}", arg)),
+        }
+    }
+
+    // 没有局数算不出平均适应度，少于两个个体也无从比较
+    if options.episodes < 1 {
+        return Err("--episodes 至少为 1".to_string());
+    }
+    if options.config.population < 2 {
+        return Err("--population 至少为 2".to_string());
+    }
+    if options.config.population <= options.config.elite {
+        return Err(format!("种群数量必须大于精英数量 {}", options.config.elite));
+    }
+    Ok(options)
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("无法解析参数值: {}", value))
+}
+
+// 每个个体在同一组种子上跑若干局，取平均适应度
+fn evaluate(genomes: &[Genome], seeds: &[u64], max_time: f32) -> Vec<f32> {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = genomes.len().div_ceil(threads);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = genomes
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|genome| {
+                            let total: f32 = seeds
+                                .iter()
+                                .map(|&seed| run_episode(genome, seed, max_time).fitness())
+                                .sum();
+                            total / seeds.len() as f32
+                        })
+                        .collect::<Vec<f32>>()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("训练线程崩溃"))
+            .collect()
+    })
+}
+
+fn main() {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+    };
+
+    let mut population = match &options.resume {
+        Some(path) => match Genome::load(path) {
+            Ok(genome) => Population::from_genome(options.config.clone(), genome, options.seed),
+            Err(e) => {
+                eprintln!("无法加载基因组 {}: {}", path.display(), e);
+                std::process::exit(1);
+            }
+        },
+        None => Population::new(options.config.clone(), options.seed),
+    };
+
+    let mut best_fitness = f32::MIN;
+    for generation in 0..options.generations {
+        // 每一代换一组地图种子，避免只记住某一局的障碍物顺序
+        let seeds: Vec<u64> = (0..options.episodes as u64)
+            .map(|i| options.seed.wrapping_mul(1_000_003) + generation as u64 * 1000 + i)
+            .collect();
+        let fitness = evaluate(&population.genomes, &seeds, options.max_time);
+
+        let (best_index, &generation_best) = fitness
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .expect("种群不能为空");
+        let average = fitness.iter().sum::<f32>() / fitness.len() as f32;
+        println!(
+            "第 {:>4} 代  最佳适应度 {:>9.1}  平均 {:>9.1}",
+            population.generation, generation_best, average
+        );
+
+        if generation_best > best_fitness {
+            best_fitness = generation_best;
+            if let Err(e) = population.genomes[best_index].save(&options.out) {
+                eprintln!("保存基因组失败 {}: {}", options.out.display(), e);
+            }
+        }
+
+        population.evolve(&fitness);
+    }
+
+    println!("训练完成，最佳基因组已保存到 {}", options.out.display());
+}
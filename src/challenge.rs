@@ -0,0 +1,60 @@
+// 每日挑战：种子由当天日期决定，同一天所有人面对相同的障碍物序列
+// 难度、命数和计分方式固定，保证分数可以互相比较；每天的最高分保存在本地，显示在菜单上
+
+use bevy::prelude::*;
+use dino_game::daily::{DailyScores, Date};
+
+use crate::achievements::{solo_run, PLAYER};
+use crate::skin::UserSettings;
+use crate::{GameScore, GameState, Lane};
+
+#[derive(Resource)]
+pub struct DailyChallenge {
+    pub active: bool,
+    pub date: Date, // 回到菜单时刷新，跨过午夜后重新开局仍算前一天的挑战
+    scores: DailyScores,
+}
+
+impl DailyChallenge {
+    pub fn best(&self) -> Option<u32> {
+        self.scores.best(self.date)
+    }
+}
+
+pub struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DailyChallenge {
+            active: false,
+            date: Date::today(0.0),
+            scores: DailyScores::load(),
+        })
+        .add_systems(OnEnter(GameState::Menu), refresh_date)
+        .add_systems(OnEnter(GameState::GameOver), record_daily_score.run_if(solo_run));
+    }
+}
+
+fn refresh_date(settings: Res<UserSettings>, mut daily: ResMut<DailyChallenge>) {
+    let today = Date::today(settings.0.utc_offset);
+    if daily.date != today {
+        daily.date = today;
+    }
+}
+
+// 只记录本地单人局里玩家自己的分数；双人、联机和 AI 代打的分数不进入每日最高分
+fn record_daily_score(mut daily: ResMut<DailyChallenge>, score_query: Query<(&GameScore, &Lane)>) {
+    if !daily.active {
+        return;
+    }
+    let Some((score, _)) = score_query.iter().find(|(_, lane)| **lane == PLAYER) else {
+        return;
+    };
+    let score = score.value;
+    let date = daily.date;
+    if daily.scores.record(date, score) {
+        if let Err(e) = daily.scores.save() {
+            warn!("保存每日挑战记录失败: {}", e);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+// 手工编排的关卡：按距离排列的障碍物、检查点和终点线
+// 关卡文件放在 assets/courses/ 下，每个文件一关，文件名（不含扩展名）作为关卡 id
+// 距离都指世界滚动的像素数：障碍物在滚动到该距离时正好到达恐龙所在的位置
+
+use std::io;
+use std::path::Path;
+
+use crate::sim::{CACTUS_CONFIGS, GAME_SPEED};
+
+pub const COURSE_DIR: &str = "courses";
+const COURSE_HEADER: &str = "dino-course v1";
+// 终点线至少要在最后一个障碍物之后这么远
+const FINISH_MARGIN: f32 = 200.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CourseObstacle {
+    pub distance: f32,
+    pub cactus: usize, // CACTUS_CONFIGS 的下标
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Course {
+    pub id: String,
+    pub name: String,
+    pub speed: f32,                     // 整关固定的世界速度（像素/秒）
+    pub obstacles: Vec<CourseObstacle>, // 按距离升序
+    pub checkpoints: Vec<f32>,          // 按距离升序，都在终点线之前
+    pub finish: f32,
+}
+
+impl Course {
+    pub fn new(id: &str) -> Course {
+        Course {
+            id: id.to_string(),
+            name: id.to_string(),
+            speed: GAME_SPEED,
+            obstacles: Vec::new(),
+            checkpoints: Vec::new(),
+            finish: 3000.0,
+        }
+    }
+
+    // 读取 assets/courses/ 下的全部关卡，按 id 排序；损坏的文件跳过，跳过的原因一并返回
+    pub fn load_all(assets_root: &Path) -> (Vec<Course>, Vec<io::Error>) {
+        let Ok(entries) = std::fs::read_dir(assets_root.join(COURSE_DIR)) else {
+            return (Vec::new(), Vec::new());
+        };
+
+        let mut courses = Vec::new();
+        let mut errors = Vec::new();
+        let paths = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "txt"));
+        for path in paths {
+            let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            match std::fs::read_to_string(&path).and_then(|content| Course::from_text(id, &content)) {
+                Ok(course) => courses.push(course),
+                Err(e) => errors.push(io::Error::new(e.kind(), format!("无法加载关卡 {}: {}", path.display(), e))),
+            }
+        }
+        courses.sort_by(|a, b| a.id.cmp(&b.id));
+        (courses, errors)
+    }
+
+    pub fn from_text(id: &str, content: &str) -> io::Result<Course> {
+        let mut lines = content.lines();
+        if lines.next().map(str::trim) != Some(COURSE_HEADER) {
+            return Err(invalid("关卡文件头不匹配"));
+        }
+
+        let mut course = Course::new(id);
+        for line in lines.map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let value = value.trim();
+            match key {
+                "name" => course.name = value.to_string(),
+                "speed" => course.speed = parse::<f32>(value)?.max(1.0),
+                "obstacle" => {
+                    let (distance, cactus) = value.split_once(' ').ok_or_else(|| invalid(format!("无效的障碍物: {}", line)))?;
+                    let cactus = parse(cactus.trim())?;
+                    if cactus >= CACTUS_CONFIGS.len() {
+                        return Err(invalid(format!("未知的障碍物种类: {}", cactus)));
+                    }
+                    course.obstacles.push(CourseObstacle {
+                        distance: parse(distance)?,
+                        cactus,
+                    });
+                }
+                "checkpoint" => course.checkpoints.push(parse(value)?),
+                "finish" => course.finish = parse(value)?,
+                _ => return Err(invalid(format!("未知的字段: {}", key))),
+            }
+        }
+        course.normalize();
+        Ok(course)
+    }
+
+    // 保存到 assets/courses/<id>.txt，编辑器使用
+    pub fn save(&self, assets_root: &Path) -> io::Result<()> {
+        let dir = assets_root.join(COURSE_DIR);
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join(format!("{}.txt", self.id)), self.to_text())
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{}\nname {}\nspeed {}\n", COURSE_HEADER, self.name, self.speed);
+        for obstacle in &self.obstacles {
+            text.push_str(&format!("obstacle {} {}\n", obstacle.distance, obstacle.cactus));
+        }
+        for checkpoint in &self.checkpoints {
+            text.push_str(&format!("checkpoint {}\n", checkpoint));
+        }
+        text.push_str(&format!("finish {}\n", self.finish));
+        text
+    }
+
+    // 排序并修正不合理的距离：终点线放在所有障碍物之后，去掉终点线之后的检查点
+    pub fn normalize(&mut self) {
+        self.obstacles.retain(|obstacle| obstacle.distance >= 0.0);
+        self.obstacles.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        if let Some(last) = self.obstacles.last() {
+            self.finish = self.finish.max(last.distance + FINISH_MARGIN);
+        }
+        let finish = self.finish;
+        self.checkpoints.retain(|checkpoint| *checkpoint > 0.0 && *checkpoint < finish);
+        self.checkpoints.sort_by(f32::total_cmp);
+        self.checkpoints.dedup();
+    }
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> io::Result<T> {
+    value.parse().map_err(|_| invalid(format!("无效的数值: {}", value)))
+}
+
+fn invalid(e: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_round_trip() {
+        let mut course = Course::new("test");
+        course.name = "Test Course".to_string();
+        course.speed = 320.0;
+        course.obstacles = vec![
+            CourseObstacle { distance: 400.0, cactus: 0 },
+            CourseObstacle { distance: 950.5, cactus: 1 },
+        ];
+        course.checkpoints = vec![600.0];
+        course.finish = 1500.0;
+        assert_eq!(Course::from_text("test", &course.to_text()).unwrap(), course);
+    }
+
+    #[test]
+    fn normalize_sorts_and_drops_out_of_range_items() {
+        let mut course = Course::new("test");
+        course.obstacles = vec![
+            CourseObstacle { distance: 900.0, cactus: 1 },
+            CourseObstacle { distance: -5.0, cactus: 0 },
+            CourseObstacle { distance: 300.0, cactus: 0 },
+        ];
+        course.checkpoints = vec![800.0, 0.0, 500.0, 500.0, 5000.0];
+        course.finish = 100.0;
+        course.normalize();
+
+        let distances: Vec<f32> = course.obstacles.iter().map(|obstacle| obstacle.distance).collect();
+        assert_eq!(distances, [300.0, 900.0]);
+        // 终点线被推到最后一个障碍物之后，之后的检查点被去掉
+        assert_eq!(course.finish, 900.0 + FINISH_MARGIN);
+        assert_eq!(course.checkpoints, [500.0, 800.0]);
+    }
+
+    #[test]
+    fn rejects_malformed_courses() {
+        assert!(Course::from_text("x", "dino-course v2\n").is_err());
+        assert!(Course::from_text("x", "dino-course v1\nobstacle 100 9\n").is_err());
+        assert!(Course::from_text("x", "dino-course v1\nobstacle 100\n").is_err());
+        assert!(Course::from_text("x", "dino-course v1\nwind 3\n").is_err());
+    }
+
+    #[test]
+    fn bundled_courses_load() {
+        let (courses, errors) = Course::load_all(&Path::new(env!("CARGO_MANIFEST_DIR")).join("assets"));
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert!(!courses.is_empty());
+        assert!(courses.windows(2).all(|pair| pair[0].id < pair[1].id));
+    }
+}
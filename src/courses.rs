@@ -0,0 +1,238 @@
+// 关卡模式：按关卡文件在指定距离生成障碍物，代替无尽模式的随机生成
+// 经过检查点后撞车，重新开局时从该检查点继续；冲过终点线即完成本关
+
+use bevy::prelude::*;
+use dino_game::course::Course;
+use dino_game::sim::{OBSTACLE_SPAWN_X, PLAYER_X};
+
+use crate::{daynight, skin, GameAssets, GameState, Lane, LocalPlayers, Player, Velocity, WorldSpeed};
+
+// 检查点和终点线的标记柱尺寸，编辑器里使用同样的样式
+pub const MARKER_SIZE: Vec2 = Vec2::new(4.0, 90.0);
+pub const CHECKPOINT_COLOR: Color = Color::srgb(0.2, 0.7, 0.3);
+pub const FINISH_COLOR: Color = Color::srgb(0.85, 0.2, 0.2);
+
+// 关卡列表与当前关卡的进度
+#[derive(Resource, Default)]
+pub struct CourseMode {
+    pub courses: Vec<Course>,
+    pub selected: Option<usize>, // None 为无尽模式
+    pub finished: bool,          // 本局是否冲过了终点线
+    traveled: f32,               // 本局世界已滚动的距离
+    resume: f32,                 // 下一局的起点，即最近经过的检查点
+    next_obstacle: usize,
+    next_checkpoint: usize,
+    finish_spawned: bool,
+}
+
+impl CourseMode {
+    pub fn course(&self) -> Option<&Course> {
+        self.selected.map(|index| &self.courses[index])
+    }
+
+    // 菜单里显示的模式名
+    pub fn label(&self) -> &str {
+        self.course().map_or("endless", |course| course.name.as_str())
+    }
+
+    // 菜单里轮换：无尽模式 → 各个关卡 → 无尽模式
+    pub fn cycle(&mut self) {
+        self.selected = match self.selected {
+            None if !self.courses.is_empty() => Some(0),
+            Some(index) if index + 1 < self.courses.len() => Some(index + 1),
+            _ => None,
+        };
+        self.resume = 0.0;
+    }
+
+    // 编辑器保存后更新关卡列表：替换同 id 的关卡或加入列表，保持当前选中的关卡不变
+    pub fn store_course(&mut self, course: Course) {
+        let selected_id = self.course().map(|selected| selected.id.clone());
+        match self.courses.iter().position(|existing| existing.id == course.id) {
+            Some(index) => self.courses[index] = course,
+            None => {
+                self.courses.push(course);
+                self.courses.sort_by(|a, b| a.id.cmp(&b.id));
+            }
+        }
+        self.selected = selected_id.and_then(|id| self.courses.iter().position(|existing| existing.id == id));
+    }
+
+    // 编辑器试玩时换上刚编辑的关卡并选中它
+    pub fn select_course(&mut self, course: Course) {
+        let id = course.id.clone();
+        self.store_course(course);
+        self.selected = self.courses.iter().position(|existing| existing.id == id);
+        self.resume = 0.0;
+    }
+
+    // 重新开局时是否从检查点继续
+    pub fn resuming(&self) -> bool {
+        self.resume > 0.0
+    }
+}
+
+// 无尽模式才随机生成障碍物
+pub fn endless(mode: Res<CourseMode>) -> bool {
+    mode.selected.is_none()
+}
+
+// 检查点和终点线的标记柱
+#[derive(Component)]
+struct CourseMarker;
+
+// 关卡名和进度
+#[derive(Component)]
+struct CourseText;
+
+pub struct CoursePlugin;
+
+impl Plugin for CoursePlugin {
+    fn build(&self, app: &mut App) {
+        let (courses, errors) = Course::load_all(&skin::assets_root());
+        for e in errors {
+            warn!("{}", e);
+        }
+        app.insert_resource(CourseMode { courses, ..default() })
+        .add_systems(OnEnter(GameState::Playing), start_course)
+        .add_systems(
+            Update,
+            (advance_course.after(crate::move_obstacles), update_course_text)
+                .chain()
+                .run_if(in_state(GameState::Playing).and(not(endless))),
+        )
+        .add_systems(OnExit(GameState::GameOver), clear_course)
+        .add_systems(OnEnter(GameState::Menu), forget_checkpoint);
+    }
+}
+
+// 每局开始时从检查点（或起点）开始，跳过之前的障碍物
+fn start_course(mut commands: Commands, mut mode: ResMut<CourseMode>) {
+    let Some(course) = mode.course() else {
+        return;
+    };
+    let start = mode.resume;
+    let next_obstacle = course.obstacles.iter().take_while(|obstacle| obstacle.distance <= start).count();
+    let next_checkpoint = course.checkpoints.iter().take_while(|checkpoint| **checkpoint <= start).count();
+
+    mode.traveled = start;
+    mode.next_obstacle = next_obstacle;
+    mode.next_checkpoint = next_checkpoint;
+    mode.finish_spawned = false;
+    mode.finished = false;
+
+    commands.spawn((
+        CourseText,
+        daynight::HudText,
+        Text2d::default(),
+        Transform::from_xyz(0.0, 175.0, 1.0),
+        TextFont {
+            font_size: 18.0,
+            ..default()
+        },
+    ));
+}
+
+// 随世界滚动推进关卡：快进入屏幕时生成障碍物和标记柱，经过检查点时记下进度，冲过终点线结束本局
+fn advance_course(
+    mut commands: Commands,
+    time: Res<Time>,
+    speed: Res<WorldSpeed>,
+    assets: Res<GameAssets>,
+    players: Res<LocalPlayers>,
+    mut mode: ResMut<CourseMode>,
+    mut next_state: ResMut<NextState<GameState>>,
+    player_query: Query<&Player, Without<crate::ghost::Ghost>>,
+) {
+    let Some(index) = mode.selected else {
+        return;
+    };
+    let mode = &mut *mode;
+    let course = &mode.courses[index];
+    mode.traveled += speed.0 * time.delta_secs();
+    let traveled = mode.traveled;
+    // 距离为 d 的物体此刻应在的横坐标
+    let x_at = |distance: f32| PLAYER_X + distance - traveled;
+    let ahead = OBSTACLE_SPAWN_X - PLAYER_X;
+
+    while let Some(obstacle) = course.obstacles.get(mode.next_obstacle) {
+        if obstacle.distance - traveled > ahead {
+            break;
+        }
+        for lane in (0..players.count).map(Lane) {
+            crate::spawn_cactus(&mut commands, &assets, obstacle.cactus, x_at(obstacle.distance), players.ground_y(lane), lane, speed.0);
+        }
+        mode.next_obstacle += 1;
+    }
+
+    // 检查点标记在到达前生成，恐龙经过后才记为新的起点
+    let spawned_checkpoints = course.checkpoints.iter().filter(|checkpoint| **checkpoint - traveled <= ahead).count();
+    for &checkpoint in &course.checkpoints[mode.next_checkpoint..spawned_checkpoints] {
+        spawn_marker(&mut commands, &players, x_at(checkpoint), CHECKPOINT_COLOR, "CP", speed.0);
+    }
+    mode.next_checkpoint = spawned_checkpoints;
+    let alive = player_query.iter().any(|player| !player.crashed);
+    if let Some(&checkpoint) = course.checkpoints.iter().rev().find(|checkpoint| **checkpoint <= traveled) {
+        if alive && checkpoint > mode.resume {
+            mode.resume = checkpoint;
+        }
+    }
+
+    if !mode.finish_spawned && course.finish - traveled <= ahead {
+        spawn_marker(&mut commands, &players, x_at(course.finish), FINISH_COLOR, "FINISH", speed.0);
+        mode.finish_spawned = true;
+    }
+    if alive && traveled >= course.finish {
+        mode.finished = true;
+        mode.resume = 0.0;
+        next_state.set(GameState::GameOver);
+    }
+}
+
+fn spawn_marker(commands: &mut Commands, players: &LocalPlayers, x: f32, color: Color, label: &str, speed: f32) {
+    for lane in (0..players.count).map(Lane) {
+        let y = players.ground_y(lane) + MARKER_SIZE.y * 0.5;
+        commands
+            .spawn((
+                CourseMarker,
+                Sprite::from_color(color, MARKER_SIZE),
+                Transform::from_xyz(x, y, 0.5),
+                lane,
+                Velocity { x: -speed },
+            ))
+            .with_child((
+                Text2d::new(label),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(color),
+                Transform::from_xyz(0.0, MARKER_SIZE.y * 0.5 + 10.0, 0.1),
+            ));
+    }
+}
+
+fn update_course_text(mode: Res<CourseMode>, mut query: Query<&mut Text2d, With<CourseText>>) {
+    let Some(course) = mode.course() else {
+        return;
+    };
+    let progress = (mode.traveled / course.finish * 100.0).clamp(0.0, 100.0);
+    let passed = course.checkpoints.iter().filter(|checkpoint| **checkpoint <= mode.resume).count();
+    let label = format!("{}  {:.0}%  CP {}/{}", course.name, progress, passed, course.checkpoints.len());
+    for mut text in query.iter_mut() {
+        if text.0 != label {
+            text.0 = label.clone();
+        }
+    }
+}
+
+fn clear_course(mut commands: Commands, query: Query<Entity, Or<(With<CourseMarker>, With<CourseText>)>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+// 回到菜单后下一局从头开始
+fn forget_checkpoint(mut mode: ResMut<CourseMode>) {
+    mode.resume = 0.0;
+}
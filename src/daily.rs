@@ -0,0 +1,183 @@
+// 每日挑战：由日期得到当天固定的种子，并记录每天的最高分
+// 标准库拿不到本地时区，日期按 UTC 时间加上设置中的时区偏移（小时）计算
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::storage;
+
+const DAILY_HEADER: &str = "dino-daily v1";
+const DAILY_FILE: &str = "daily_scores.txt";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn today(utc_offset_hours: f32) -> Date {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() as i64);
+        let local = secs + (utc_offset_hours * 3600.0) as i64;
+        Date::from_days(local.div_euclid(86_400))
+    }
+
+    // 1970-01-01 起的天数换算成公历日期（Howard Hinnant 的 civil_from_days 算法）
+    pub fn from_days(days: i64) -> Date {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153; // 从三月开始数
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+        let year = year_of_era + era * 400 + (month <= 2) as i64;
+        Date {
+            year: year as i32,
+            month: month as u32,
+            day: day as u32,
+        }
+    }
+
+    // 形如 2024-05-01
+    pub fn parse(text: &str) -> Option<Date> {
+        let mut parts = text.split('-');
+        let date = Date {
+            year: parts.next()?.parse().ok()?,
+            month: parts.next()?.parse().ok()?,
+            day: parts.next()?.parse().ok()?,
+        };
+        let valid = parts.next().is_none() && (1..=12).contains(&date.month) && (1..=31).contains(&date.day);
+        valid.then_some(date)
+    }
+
+    // 当天的障碍物种子：日期数字经过 SplitMix64 打散，相邻两天的种子毫无关联
+    pub fn seed(self) -> u64 {
+        let mut z = (self.year as u64 * 10_000 + self.month as u64 * 100 + self.day as u64)
+            .wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+// 每天的最高分，保存在存档目录的 daily_scores.txt 中
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DailyScores {
+    best: BTreeMap<Date, u32>,
+}
+
+impl DailyScores {
+    // 文件不存在或损坏时从空记录开始
+    pub fn load() -> DailyScores {
+        storage::read_file(DAILY_FILE)
+            .ok()
+            .and_then(|content| DailyScores::from_text(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        storage::write_file(DAILY_FILE, &self.to_text())
+    }
+
+    pub fn best(&self, date: Date) -> Option<u32> {
+        self.best.get(&date).copied()
+    }
+
+    // 记录一局的分数，刷新当天最高分时返回 true
+    pub fn record(&mut self, date: Date, score: u32) -> bool {
+        let best = self.best.entry(date).or_insert(0);
+        if score > *best {
+            *best = score;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut content = format!("{}\n", DAILY_HEADER);
+        for (date, score) in &self.best {
+            content.push_str(&format!("{} {}\n", date, score));
+        }
+        content
+    }
+
+    pub fn from_text(content: &str) -> io::Result<DailyScores> {
+        let mut lines = content.lines();
+        if lines.next().map(str::trim) != Some(DAILY_HEADER) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "每日挑战记录文件头不匹配"));
+        }
+
+        let mut scores = DailyScores::default();
+        for line in lines {
+            let Some((date, score)) = line.trim().split_once(' ') else {
+                continue;
+            };
+            if let (Some(date), Ok(score)) = (Date::parse(date), score.trim().parse()) {
+                scores.best.insert(date, score);
+            }
+        }
+        Ok(scores)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> Date {
+        Date { year, month, day }
+    }
+
+    #[test]
+    fn from_days_handles_epoch_leap_years_and_negatives() {
+        assert_eq!(Date::from_days(0), date(1970, 1, 1));
+        assert_eq!(Date::from_days(-1), date(1969, 12, 31));
+        assert_eq!(Date::from_days(59), date(1970, 3, 1));
+        assert_eq!(Date::from_days(11_016), date(2000, 2, 29));
+        assert_eq!(Date::from_days(19_723), date(2024, 1, 1));
+        assert_eq!(Date::from_days(20_744), date(2026, 10, 18));
+    }
+
+    #[test]
+    fn parse_accepts_display_output_and_rejects_garbage() {
+        let day = date(2024, 5, 1);
+        assert_eq!(day.to_string(), "2024-05-01");
+        assert_eq!(Date::parse(&day.to_string()), Some(day));
+        assert_eq!(Date::parse("2024-5-1"), Some(day));
+        for text in ["", "2024-05", "2024-13-01", "2024-05-00", "2024-05-01-02", "a-b-c"] {
+            assert_eq!(Date::parse(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn seeds_differ_between_days() {
+        assert_eq!(date(2024, 5, 1).seed(), date(2024, 5, 1).seed());
+        assert_ne!(date(2024, 5, 1).seed(), date(2024, 5, 2).seed());
+    }
+
+    #[test]
+    fn scores_keep_the_best_and_round_trip() {
+        let mut scores = DailyScores::default();
+        assert!(scores.record(date(2024, 5, 1), 10));
+        assert!(!scores.record(date(2024, 5, 1), 7));
+        assert!(scores.record(date(2024, 5, 2), 3));
+        assert_eq!(scores.best(date(2024, 5, 1)), Some(10));
+        assert_eq!(scores.best(date(2024, 5, 3)), None);
+        assert_eq!(DailyScores::from_text(&scores.to_text()).unwrap(), scores);
+        assert!(DailyScores::from_text("dino-daily v2\n").is_err());
+    }
+}
@@ -0,0 +1,157 @@
+// 昼夜交替：和 Chrome 原版一样，每跑过一定分数就在白天和黑夜之间切换
+// 天空、地面、精灵、HUD 文字和背景颜色都在两套配色之间平滑过渡，夜晚还会出现月亮和星星
+
+use bevy::prelude::*;
+
+use crate::{GameScore, Ground, Obstacle, Player};
+
+// 一套配色；精灵和地面颜色会与贴图相乘
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub sky: Color,
+    pub ground: Color,
+    pub sprite: Color,
+    pub hud_text: Color,
+    pub background: Color, // 远山、云朵等背景图层的色调
+}
+
+impl Palette {
+    fn mix(&self, other: &Palette, t: f32) -> Palette {
+        Palette {
+            sky: self.sky.mix(&other.sky, t),
+            ground: self.ground.mix(&other.ground, t),
+            sprite: self.sprite.mix(&other.sprite, t),
+            hud_text: self.hud_text.mix(&other.hud_text, t),
+            background: self.background.mix(&other.background, t),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct DayNightConfig {
+    pub day: Palette,
+    pub night: Palette,
+    pub cycle_points: u32,    // 每跳过多少个障碍物切换一次昼夜
+    pub transition_secs: f32, // 过渡所需时间
+}
+
+impl Default for DayNightConfig {
+    fn default() -> Self {
+        Self {
+            day: Palette {
+                sky: Color::srgb(0.9, 0.9, 0.9), // 灰白色背景
+                ground: Color::srgb(0.55, 0.27, 0.07), // 棕色色调
+                sprite: Color::WHITE,
+                hud_text: Color::srgb(0.2, 0.2, 0.2),
+                background: Color::WHITE,
+            },
+            night: Palette {
+                sky: Color::srgb(0.13, 0.14, 0.2),
+                ground: Color::srgb(0.32, 0.24, 0.3),
+                sprite: Color::srgb(0.75, 0.8, 1.0), // 月光下偏冷的色调
+                hud_text: Color::srgb(0.92, 0.92, 0.92),
+                background: Color::srgb(0.3, 0.33, 0.45),
+            },
+            cycle_points: 30,
+            transition_secs: 1.5,
+        }
+    }
+}
+
+// 当前的昼夜进度：0 为白天，1 为黑夜
+#[derive(Resource, Default)]
+pub struct DayNight {
+    pub night: f32,
+    is_night: bool,
+}
+
+impl DayNight {
+    // 是否已经入夜（配色可能还在过渡中）
+    pub fn is_night(&self) -> bool {
+        self.is_night
+    }
+}
+
+// 当前混合后的配色，其他模块生成新实体时使用
+#[derive(Resource)]
+pub struct CurrentPalette(pub Palette);
+
+// 跟随昼夜变色的 HUD 文字
+#[derive(Component)]
+pub struct HudText;
+
+pub struct DayNightPlugin;
+
+impl Plugin for DayNightPlugin {
+    fn build(&self, app: &mut App) {
+        let config = DayNightConfig::default();
+        app.insert_resource(ClearColor(config.day.sky))
+            .insert_resource(CurrentPalette(config.day))
+            .insert_resource(config)
+            .init_resource::<DayNight>()
+            .add_systems(Update, (update_cycle, blend_palette).chain())
+            // 放在 PostUpdate，这样本帧刚生成的精灵也能在渲染前染上当前配色
+            .add_systems(PostUpdate, apply_palette);
+    }
+}
+
+// 根据跳过障碍物最多的玩家决定现在应该是白天还是黑夜，与计分方式无关；重新开局后归零会回到白天
+fn update_cycle(config: Res<DayNightConfig>, mut day_night: ResMut<DayNight>, score_query: Query<&GameScore>) {
+    let best = score_query.iter().map(|score| score.cleared).max().unwrap_or(0);
+    let is_night = config.cycle_points > 0 && (best / config.cycle_points) % 2 == 1;
+    if day_night.is_night != is_night {
+        day_night.is_night = is_night;
+    }
+}
+
+pub fn blend_palette(
+    time: Res<Time>,
+    config: Res<DayNightConfig>,
+    mut day_night: ResMut<DayNight>,
+    mut palette: ResMut<CurrentPalette>,
+    mut clear_color: ResMut<ClearColor>,
+) {
+    let target = if day_night.is_night { 1.0 } else { 0.0 };
+    if day_night.night == target && !config.is_changed() {
+        return;
+    }
+
+    let step = time.delta_secs() / config.transition_secs.max(f32::EPSILON);
+    day_night.night = if day_night.night < target {
+        (day_night.night + step).min(target)
+    } else {
+        (day_night.night - step).max(target)
+    };
+
+    palette.0 = config.day.mix(&config.night, day_night.night);
+    clear_color.0 = palette.0.sky;
+}
+
+// 配色变化时给所有实体重新上色，否则只处理新生成的实体；精灵保留原有的透明度
+fn apply_palette(
+    palette: Res<CurrentPalette>,
+    mut ground_query: Query<&mut Sprite, With<Ground>>,
+    mut sprite_query: Query<&mut Sprite, (Or<(With<Player>, With<Obstacle>)>, Without<Ground>)>,
+    mut text_query: Query<&mut TextColor, With<HudText>>,
+) {
+    let recolor_all = palette.is_changed();
+
+    for mut sprite in ground_query.iter_mut() {
+        if recolor_all || sprite.is_added() {
+            sprite.color = palette.0.ground;
+        }
+    }
+
+    for mut sprite in sprite_query.iter_mut() {
+        if recolor_all || sprite.is_added() {
+            let alpha = sprite.color.alpha();
+            sprite.color = palette.0.sprite.with_alpha(alpha);
+        }
+    }
+
+    for mut color in text_query.iter_mut() {
+        if recolor_all || color.is_added() {
+            color.0 = palette.0.hud_text;
+        }
+    }
+}
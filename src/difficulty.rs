@@ -0,0 +1,248 @@
+// 难度曲线：按分数分段配置速度、障碍物生成概率、间隔、种类和成簇数量
+// 数据来自 assets/data/difficulty.txt，分 easy、normal、hard 三个预设；缺少的预设使用内置默认值
+
+use std::io;
+use std::path::Path;
+
+use crate::sim::{CACTUS_CONFIGS, GAME_SPEED, OBSTACLE_INTERVAL, OBSTACLE_PROBABILITY};
+
+pub const DIFFICULTY_FILE: &str = "data/difficulty.txt";
+const DIFFICULTY_HEADER: &str = "dino-difficulty v1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        Difficulty::ALL.into_iter().find(|difficulty| difficulty.name() == name)
+    }
+
+    // 菜单里轮换到下一个难度
+    pub fn next(self) -> Difficulty {
+        Difficulty::ALL[(self as usize + 1) % Difficulty::ALL.len()]
+    }
+}
+
+// 从某个分数开始生效的一段难度
+#[derive(Debug, Clone, PartialEq)]
+pub struct Band {
+    pub from: u32,           // 起始分数（跳过的障碍物数量）
+    pub speed: f32,          // 世界滚动速度（像素/秒）
+    pub chance: f64,         // 每次计时到点时生成障碍物的概率
+    pub gap: (f32, f32),     // 下一次生成的间隔范围（秒）
+    pub types: Vec<usize>,   // 允许出现的障碍物，CACTUS_CONFIGS 的下标
+    pub cluster: (u32, u32), // 一次并排生成的障碍物数量范围
+}
+
+impl Default for Band {
+    // 与模拟器使用的常量一致
+    fn default() -> Self {
+        Self {
+            from: 0,
+            speed: GAME_SPEED,
+            chance: OBSTACLE_PROBABILITY,
+            gap: (OBSTACLE_INTERVAL.start, OBSTACLE_INTERVAL.end),
+            types: (0..CACTUS_CONFIGS.len()).collect(),
+            cluster: (1, 1),
+        }
+    }
+}
+
+impl Band {
+    fn from_fields(fields: &[&str]) -> io::Result<Band> {
+        let mut fields = fields.iter().copied();
+        let from = parse(fields.next().unwrap_or(""))?;
+        let mut band = Band {
+            from,
+            ..Band::default()
+        };
+
+        let mut next = |name: &str| fields.next().ok_or_else(|| invalid(format!("{} 缺少数值", name)));
+        while let Ok(key) = next("band") {
+            match key {
+                "speed" => band.speed = parse::<f32>(next(key)?)?.max(1.0),
+                "chance" => band.chance = parse::<f64>(next(key)?)?.clamp(0.0, 1.0),
+                "gap" => band.gap = (parse(next(key)?)?, parse(next(key)?)?),
+                "types" => {
+                    band.types = next(key)?
+                        .split(',')
+                        .map(parse)
+                        .filter(|index| index.as_ref().map_or(true, |index| *index < CACTUS_CONFIGS.len()))
+                        .collect::<io::Result<_>>()?
+                }
+                "cluster" => band.cluster = (parse(next(key)?)?, parse(next(key)?)?),
+                _ => return Err(invalid(format!("未知的字段: {}", key))),
+            }
+        }
+
+        // 修正不合理的配置，保证生成时不会出错
+        band.gap.0 = band.gap.0.max(0.1);
+        band.gap.1 = band.gap.1.max(band.gap.0);
+        band.cluster.0 = band.cluster.0.max(1);
+        band.cluster.1 = band.cluster.1.max(band.cluster.0);
+        if band.types.is_empty() {
+            band.types = Band::default().types;
+        }
+        Ok(band)
+    }
+}
+
+// 一个预设的完整曲线，按起始分数升序排列
+#[derive(Debug, Clone, PartialEq)]
+pub struct DifficultyCurve {
+    pub bands: Vec<Band>,
+}
+
+impl Default for DifficultyCurve {
+    fn default() -> Self {
+        Self {
+            bands: vec![Band::default()],
+        }
+    }
+}
+
+impl DifficultyCurve {
+    // 当前分数所在的分段
+    pub fn band(&self, score: u32) -> &Band {
+        self.bands
+            .iter()
+            .rev()
+            .find(|band| band.from <= score)
+            .unwrap_or(&self.bands[0])
+    }
+}
+
+// 三个预设，按 Difficulty::ALL 的顺序
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DifficultyPresets {
+    curves: [DifficultyCurve; 3],
+}
+
+impl DifficultyPresets {
+    pub fn curve(&self, difficulty: Difficulty) -> &DifficultyCurve {
+        &self.curves[difficulty as usize]
+    }
+
+    pub fn load(assets_root: &Path) -> io::Result<DifficultyPresets> {
+        DifficultyPresets::from_text(&std::fs::read_to_string(assets_root.join(DIFFICULTY_FILE))?)
+    }
+
+    pub fn from_text(content: &str) -> io::Result<DifficultyPresets> {
+        let mut lines = content.lines();
+        if lines.next().map(str::trim) != Some(DIFFICULTY_HEADER) {
+            return Err(invalid("难度配置文件头不匹配"));
+        }
+
+        let mut bands: [Vec<Band>; 3] = Default::default();
+        let mut current = None;
+        for line in lines.map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields[0] {
+                "preset" => {
+                    let name = fields.get(1).copied().unwrap_or("");
+                    let difficulty = Difficulty::from_name(name).ok_or_else(|| invalid(format!("未知的预设: {}", name)))?;
+                    current = Some(difficulty);
+                }
+                "band" => {
+                    let difficulty = current.ok_or_else(|| invalid("band 之前缺少 preset"))?;
+                    bands[difficulty as usize].push(Band::from_fields(&fields[1..])?);
+                }
+                _ => {}
+            }
+        }
+
+        let mut presets = DifficultyPresets::default();
+        for (curve, mut bands) in presets.curves.iter_mut().zip(bands) {
+            if !bands.is_empty() {
+                bands.sort_by_key(|band| band.from);
+                curve.bands = bands;
+            }
+        }
+        Ok(presets)
+    }
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> io::Result<T> {
+    value.parse().map_err(|_| invalid(format!("无效的数值: {}", value)))
+}
+
+fn invalid(e: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_presets_parse_with_sorted_bands() {
+        let presets = DifficultyPresets::from_text(include_str!("../assets/data/difficulty.txt")).unwrap();
+        for difficulty in Difficulty::ALL {
+            let bands = &presets.curve(difficulty).bands;
+            assert_eq!(bands[0].from, 0, "{}", difficulty.name());
+            assert!(bands.windows(2).all(|pair| pair[0].from <= pair[1].from));
+        }
+    }
+
+    #[test]
+    fn band_lookup_uses_the_last_band_reached() {
+        let presets = DifficultyPresets::from_text(
+            "dino-difficulty v1\npreset hard\nband 10 speed 400\nband 0 speed 300\n",
+        )
+        .unwrap();
+        let curve = presets.curve(Difficulty::Hard);
+        assert_eq!(curve.band(0).speed, 300.0);
+        assert_eq!(curve.band(9).speed, 300.0);
+        assert_eq!(curve.band(10).speed, 400.0);
+        // 没有配置的预设使用内置默认值
+        assert_eq!(presets.curve(Difficulty::Easy), &DifficultyCurve::default());
+    }
+
+    #[test]
+    fn fixes_unreasonable_bands() {
+        let presets = DifficultyPresets::from_text(
+            "dino-difficulty v1\npreset normal\nband 0 gap 0 -1 cluster 0 0 types 99 chance 5\n",
+        )
+        .unwrap();
+        let band = presets.curve(Difficulty::Normal).band(0);
+        assert_eq!(band.gap, (0.1, 0.1));
+        assert_eq!(band.cluster, (1, 1));
+        assert_eq!(band.types, Band::default().types);
+        assert_eq!(band.chance, 1.0);
+    }
+
+    #[test]
+    fn rejects_malformed_config() {
+        assert!(DifficultyPresets::from_text("dino-difficulty v2\n").is_err());
+        assert!(DifficultyPresets::from_text("dino-difficulty v1\nband 0 speed 300\n").is_err());
+        assert!(DifficultyPresets::from_text("dino-difficulty v1\npreset brutal\n").is_err());
+        assert!(DifficultyPresets::from_text("dino-difficulty v1\npreset easy\nband 0 speed\n").is_err());
+        assert!(DifficultyPresets::from_text("dino-difficulty v1\npreset easy\nband 0 jump 1\n").is_err());
+    }
+
+    #[test]
+    fn names_round_trip_and_next_cycles() {
+        for difficulty in Difficulty::ALL {
+            assert_eq!(Difficulty::from_name(difficulty.name()), Some(difficulty));
+        }
+        assert_eq!(Difficulty::Hard.next(), Difficulty::Easy);
+    }
+}
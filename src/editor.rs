@@ -0,0 +1,368 @@
+// 关卡编辑器：世界暂停，左右滚动查看整条关卡，用鼠标在地面上放置、拖动和删除障碍物
+// 编辑菜单里选中的关卡（无尽模式时新建一个），S 保存，回车保存并立即试玩，试玩结束后按 E 回到编辑器
+
+use bevy::input::mouse::AccumulatedMouseScroll;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use dino_game::course::{Course, CourseObstacle, COURSE_DIR};
+use dino_game::sim::{CACTUS_CONFIGS, PLAYER_X, WINDOW_WIDTH};
+
+use crate::challenge::DailyChallenge;
+use crate::courses::{CourseMode, CHECKPOINT_COLOR, FINISH_COLOR, MARKER_SIZE};
+use crate::{daynight, skin, GameAssets, GameState, Lane, LocalPlayers};
+
+const SCROLL_SPEED: f32 = 600.0; // 方向键滚动速度（像素/秒）
+const WHEEL_STEP: f32 = 40.0; // 鼠标滚轮每格滚动的距离
+const SNAP: f32 = 5.0; // 距离按这个步长对齐
+const PICK_RADIUS: f32 = 20.0; // 点击位置离物体这么近时视为选中
+const GROUND_REACH: f32 = 80.0; // 光标离地面这么近时才能放置和删除
+const RULER_STEP: f32 = 500.0; // 距离刻度的间隔
+const NEW_COURSE_ID: &str = "custom";
+
+// 编辑器里可以选中的物体
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Item {
+    Obstacle(usize),
+    Checkpoint(usize),
+    Finish,
+}
+
+#[derive(Resource)]
+struct Editor {
+    course: Course,
+    scroll: f32,            // 恐龙所在位置对应的关卡距离
+    cactus: usize,          // 新放置的障碍物种类
+    dragging: Option<Item>, // 按住左键拖动中的物体
+    cursor: Option<f32>,    // 光标所在的关卡距离
+    status: String,         // 最近一次操作的结果
+}
+
+// 编辑器画出来的物体，关卡变化时整体重画
+#[derive(Component)]
+struct EditorSprite;
+
+#[derive(Component)]
+struct EditorText;
+
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Editor), open_editor)
+            .add_systems(OnExit(GameState::Editor), close_editor)
+            .add_systems(
+                Update,
+                (scroll_view, edit_with_mouse, edit_with_keys, redraw.run_if(resource_changed::<Editor>))
+                    .chain()
+                    .run_if(in_state(GameState::Editor)),
+            )
+            .add_systems(
+                Update,
+                enter_editor.run_if(
+                    in_state(GameState::Menu)
+                        .or(in_state(GameState::GameOver))
+                        .and(not(resource_exists::<crate::netplay::NetSession>))
+                        .and(crate::menu::main_page)
+                        .and(not(crate::leaderboards::typing)),
+                ),
+            );
+    }
+}
+
+// 菜单里按 E 打开编辑器；关卡模式结束后按 E 回到编辑器继续修改
+fn enter_editor(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    course_mode: Res<CourseMode>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let allowed = *state.get() == GameState::Menu || course_mode.selected.is_some();
+    if allowed && keyboard_input.just_pressed(KeyCode::KeyE) {
+        next_state.set(GameState::Editor);
+    }
+}
+
+fn open_editor(mut commands: Commands, course_mode: Res<CourseMode>) {
+    let course = course_mode.course().cloned().unwrap_or_else(|| new_course(&course_mode));
+    commands.insert_resource(Editor {
+        status: format!("Editing courses/{}.txt", course.id),
+        course,
+        scroll: 0.0,
+        cactus: 0,
+        dragging: None,
+        cursor: None,
+    });
+    commands.spawn((
+        EditorText,
+        daynight::HudText,
+        Text2d::default(),
+        Transform::from_xyz(0.0, 150.0, 10.0),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+    ));
+}
+
+// 新关卡使用还没被占用的 id：既不在关卡列表里，也没有同名的关卡文件，不会覆盖已有的关卡
+fn new_course(course_mode: &CourseMode) -> Course {
+    let dir = skin::assets_root().join(COURSE_DIR);
+    let taken = |id: &str| {
+        course_mode.courses.iter().any(|course| course.id == id) || dir.join(format!("{}.txt", id)).exists()
+    };
+    let id = std::iter::once(NEW_COURSE_ID.to_string())
+        .chain((2..).map(|n| format!("{}{}", NEW_COURSE_ID, n)))
+        .find(|id| !taken(id))
+        .unwrap_or_default();
+    Course::new(&id)
+}
+
+fn close_editor(mut commands: Commands, query: Query<Entity, Or<(With<EditorSprite>, With<EditorText>)>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+    commands.remove_resource::<Editor>();
+}
+
+// 左右方向键或鼠标滚轮滚动视图
+fn scroll_view(
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    wheel: Res<AccumulatedMouseScroll>,
+    mut editor: ResMut<Editor>,
+) {
+    let mut delta = (wheel.delta.y + wheel.delta.x) * WHEEL_STEP;
+    if keyboard_input.pressed(KeyCode::ArrowRight) {
+        delta += SCROLL_SPEED * time.delta_secs();
+    }
+    if keyboard_input.pressed(KeyCode::ArrowLeft) {
+        delta -= SCROLL_SPEED * time.delta_secs();
+    }
+    // 只在真的滚动时修改，避免每帧触发重画
+    if delta != 0.0 {
+        let max_scroll = editor.course.finish;
+        editor.scroll = (editor.scroll + delta).clamp(0.0, max_scroll);
+    }
+}
+
+// 左键在地面上放置或拖动，右键删除
+fn edit_with_mouse(
+    buttons: Res<ButtonInput<MouseButton>>,
+    players: Res<LocalPlayers>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    mut editor: ResMut<Editor>,
+) {
+    let ground_y = players.ground_y(Lane(0));
+    let cursor = windows
+        .single()
+        .ok()
+        .and_then(|window| window.cursor_position())
+        .zip(camera_query.single().ok())
+        .and_then(|(position, (camera, transform))| camera.viewport_to_world_2d(transform, position).ok())
+        .filter(|world| (world.y - ground_y).abs() < GROUND_REACH)
+        .map(|world| ((editor.scroll + world.x - PLAYER_X) / SNAP).round().max(0.0) * SNAP);
+    if editor.cursor != cursor {
+        editor.cursor = cursor;
+    }
+
+    if buttons.just_released(MouseButton::Left) && editor.dragging.take().is_some() {
+        editor.course.normalize();
+    }
+    let Some(distance) = cursor else {
+        return;
+    };
+
+    if buttons.just_pressed(MouseButton::Left) {
+        let item = pick(&editor.course, distance).unwrap_or_else(|| {
+            let cactus = editor.cactus;
+            editor.course.obstacles.push(CourseObstacle { distance, cactus });
+            Item::Obstacle(editor.course.obstacles.len() - 1)
+        });
+        editor.dragging = Some(item);
+    } else if buttons.pressed(MouseButton::Left) {
+        if let Some(item) = editor.dragging {
+            if item_distance(&editor.course, item) != distance {
+                match item {
+                    Item::Obstacle(index) => editor.course.obstacles[index].distance = distance,
+                    Item::Checkpoint(index) => editor.course.checkpoints[index] = distance,
+                    Item::Finish => editor.course.finish = distance,
+                }
+            }
+        }
+    }
+
+    if buttons.just_pressed(MouseButton::Right) && editor.dragging.is_none() {
+        match pick(&editor.course, distance) {
+            Some(Item::Obstacle(index)) => {
+                editor.course.obstacles.remove(index);
+            }
+            Some(Item::Checkpoint(index)) => {
+                editor.course.checkpoints.remove(index);
+            }
+            // 终点线不能删除，只能拖动
+            Some(Item::Finish) | None => {}
+        }
+    }
+}
+
+// 离光标最近、且在选取范围内的物体；障碍物优先
+fn pick(course: &Course, distance: f32) -> Option<Item> {
+    let near = |d: f32| (d - distance).abs() < PICK_RADIUS;
+    let closest = |items: &mut dyn Iterator<Item = (usize, f32)>| {
+        items
+            .filter(|(_, d)| near(*d))
+            .min_by(|a, b| (a.1 - distance).abs().total_cmp(&(b.1 - distance).abs()))
+            .map(|(index, _)| index)
+    };
+
+    closest(&mut course.obstacles.iter().map(|obstacle| obstacle.distance).enumerate())
+        .map(Item::Obstacle)
+        .or_else(|| closest(&mut course.checkpoints.iter().copied().enumerate()).map(Item::Checkpoint))
+        .or_else(|| near(course.finish).then_some(Item::Finish))
+}
+
+fn item_distance(course: &Course, item: Item) -> f32 {
+    match item {
+        Item::Obstacle(index) => course.obstacles[index].distance,
+        Item::Checkpoint(index) => course.checkpoints[index],
+        Item::Finish => course.finish,
+    }
+}
+
+// 1/2 选择仙人掌种类，C 在光标处加检查点，S 保存，回车保存并试玩，ESC 回到菜单
+fn edit_with_keys(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut editor: ResMut<Editor>,
+    mut course_mode: ResMut<CourseMode>,
+    mut daily: ResMut<DailyChallenge>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let pressed = |key: KeyCode| keyboard_input.just_pressed(key);
+    if pressed(KeyCode::Digit1) {
+        editor.cactus = 0;
+    } else if pressed(KeyCode::Digit2) {
+        editor.cactus = 1.min(CACTUS_CONFIGS.len() - 1);
+    } else if pressed(KeyCode::KeyC) {
+        // 光标不在地面附近时放在屏幕中央
+        let distance = editor.cursor.unwrap_or(editor.scroll - PLAYER_X);
+        editor.course.checkpoints.push(distance);
+        editor.course.normalize();
+    } else if pressed(KeyCode::KeyS) || pressed(KeyCode::Enter) {
+        editor.course.normalize();
+        editor.status = match editor.course.save(&skin::assets_root()) {
+            Ok(()) => {
+                // 保存后立即出现在菜单的关卡列表里，下次新建关卡也会避开这个 id
+                course_mode.store_course(editor.course.clone());
+                format!("Saved courses/{}.txt", editor.course.id)
+            }
+            Err(e) => format!("Save failed: {}", e),
+        };
+        if pressed(KeyCode::Enter) {
+            course_mode.select_course(editor.course.clone());
+            daily.active = false;
+            next_state.set(GameState::Playing);
+        }
+    } else if pressed(KeyCode::Escape) {
+        next_state.set(GameState::Menu);
+    }
+}
+
+// 关卡或视图变化时重画屏幕范围内的物体和说明文字
+fn redraw(
+    mut commands: Commands,
+    editor: Res<Editor>,
+    assets: Res<GameAssets>,
+    players: Res<LocalPlayers>,
+    sprites: Query<Entity, With<EditorSprite>>,
+    mut text_query: Query<&mut Text2d, With<EditorText>>,
+) {
+    for entity in sprites.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let ground_y = players.ground_y(Lane(0));
+    let x_at = |distance: f32| PLAYER_X + distance - editor.scroll;
+    let visible = |x: f32| x.abs() < WINDOW_WIDTH * 0.5 + 50.0;
+
+    for (index, obstacle) in editor.course.obstacles.iter().enumerate() {
+        let x = x_at(obstacle.distance);
+        if !visible(x) {
+            continue;
+        }
+        let (width, height) = CACTUS_CONFIGS[obstacle.cactus];
+        let dragged = editor.dragging == Some(Item::Obstacle(index));
+        commands.spawn((
+            EditorSprite,
+            Sprite {
+                image: assets.cactus_textures[obstacle.cactus].clone(),
+                custom_size: Some(Vec2::new(width, height)),
+                color: Color::WHITE.with_alpha(if dragged { 0.6 } else { 1.0 }),
+                ..default()
+            },
+            Transform::from_xyz(x, ground_y + height * 0.5, 1.0),
+        ));
+    }
+
+    let markers = editor
+        .course
+        .checkpoints
+        .iter()
+        .map(|&distance| (distance, CHECKPOINT_COLOR, "CP"))
+        .chain(std::iter::once((editor.course.finish, FINISH_COLOR, "FINISH")));
+    for (distance, color, label) in markers {
+        let x = x_at(distance);
+        if !visible(x) {
+            continue;
+        }
+        commands
+            .spawn((
+                EditorSprite,
+                Sprite::from_color(color, MARKER_SIZE),
+                Transform::from_xyz(x, ground_y + MARKER_SIZE.y * 0.5, 0.5),
+            ))
+            .with_child((
+                Text2d::new(label),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(color),
+                Transform::from_xyz(0.0, MARKER_SIZE.y * 0.5 + 10.0, 0.1),
+            ));
+    }
+
+    // 地面下方的距离刻度
+    let first = (editor.scroll / RULER_STEP).floor() as i32 - 1;
+    for step in first..first + (WINDOW_WIDTH / RULER_STEP) as i32 + 3 {
+        let distance = step as f32 * RULER_STEP;
+        let x = x_at(distance);
+        if distance < 0.0 || !visible(x) {
+            continue;
+        }
+        commands.spawn((
+            EditorSprite,
+            daynight::HudText,
+            Text2d::new(format!("{}", distance)),
+            TextFont {
+                font_size: 12.0,
+                ..default()
+            },
+            Transform::from_xyz(x, ground_y - 22.0, 1.0),
+        ));
+    }
+
+    let cursor = editor.cursor.map_or(String::from("-"), |distance| format!("{}", distance));
+    let label = format!(
+        "EDITOR  {}  cactus: {}  cursor: {}\nLMB place/drag  RMB delete  1/2 cactus  C checkpoint  <-/-> scroll\nS save  ENTER save & playtest  ESC menu\n{}",
+        editor.course.name,
+        editor.cactus + 1,
+        cursor,
+        editor.status
+    );
+    for mut text in text_query.iter_mut() {
+        if text.0 != label {
+            text.0 = label.clone();
+        }
+    }
+}
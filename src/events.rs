@@ -0,0 +1,72 @@
+// 游戏过程中的关键时刻，由核心系统发出；音效、粒子、成就、统计等模块监听这些事件作出反应，
+// 不需要改动核心系统。幽灵不会触发这些事件
+
+use bevy::prelude::*;
+
+use crate::Lane;
+
+// 起跳；air_jump 表示二段跳道具提供的空中再跳，position 为恐龙的位置
+#[derive(Event)]
+pub struct Jumped {
+    pub lane: Lane,
+    pub air_jump: bool,
+    pub position: Vec2,
+}
+
+// 从空中落回地面，position 为恐龙脚下的地面位置
+#[derive(Event)]
+pub struct Landed {
+    pub lane: Lane,
+    pub position: Vec2,
+}
+
+// 跳过了一个障碍物，cactus 为 CACTUS_CONFIGS 的下标
+#[derive(Event)]
+pub struct ObstacleCleared {
+    pub lane: Lane,
+    pub cactus: usize,
+}
+
+// 撞上障碍物丢了一条命；lives_left 为 0 表示这名玩家本局结束，position 为撞击点
+#[derive(Event)]
+pub struct Crashed {
+    pub lane: Lane,
+    pub cactus: usize,
+    pub lives_left: u32,
+    pub position: Vec2,
+}
+
+// 护盾挡下撞击，障碍物被撞碎；不算撞车
+#[derive(Event)]
+pub struct ObstacleSmashed {
+    pub position: Vec2,
+}
+
+// 吃到一枚金币
+#[derive(Event)]
+pub struct CoinCollected;
+
+// 吃到一个道具
+#[derive(Event)]
+pub struct PowerUpCollected;
+
+// 分数跨过 MILESTONE_POINTS 的整数倍
+#[derive(Event)]
+pub struct MilestoneReached {
+    pub lane: Lane,
+}
+
+pub struct GameEventsPlugin;
+
+impl Plugin for GameEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Jumped>()
+            .add_event::<Landed>()
+            .add_event::<ObstacleCleared>()
+            .add_event::<Crashed>()
+            .add_event::<ObstacleSmashed>()
+            .add_event::<CoinCollected>()
+            .add_event::<PowerUpCollected>()
+            .add_event::<MilestoneReached>();
+    }
+}
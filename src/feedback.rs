@@ -0,0 +1,107 @@
+// 撞车反馈：全部玩家撞车后先定格片刻（hit-stop）再进入游戏结束，同时镜头随衰减的“创伤值”抖动
+// 抖动强度和定格时长都来自设置，可以关闭；两者都按真实时间计算，不受定格影响
+
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::events::Crashed;
+use crate::ghost::Ghost;
+use crate::netplay::NetSession;
+use crate::skin::UserSettings;
+use crate::{GameState, Player};
+
+// 每次撞车增加的创伤值，抖动幅度与创伤值的平方成正比
+const CRASH_TRAUMA: f32 = 0.8;
+// 每秒衰减的创伤值
+const TRAUMA_DECAY: f32 = 1.5;
+// 创伤值为 1、抖动强度为 1 时的最大偏移（像素）和最大旋转（弧度）
+const MAX_OFFSET: f32 = 14.0;
+const MAX_ANGLE: f32 = 0.05;
+
+#[derive(Resource, Default)]
+struct Trauma(f32);
+
+// 剩余的定格时间
+#[derive(Resource, Default)]
+struct HitStop(Option<f32>);
+
+pub struct FeedbackPlugin;
+
+impl Plugin for FeedbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Trauma>()
+            .init_resource::<HitStop>()
+            .add_systems(Update, (handle_crashes, tick_hit_stop, shake_camera).chain());
+    }
+}
+
+// 每次撞车都让镜头抖动；最后一名玩家的命用完时本局结束
+fn handle_crashes(
+    mut crashes: EventReader<Crashed>,
+    settings: Res<UserSettings>,
+    net_session: Option<Res<NetSession>>,
+    player_query: Query<&Player, Without<Ghost>>,
+    mut trauma: ResMut<Trauma>,
+    mut hit_stop: ResMut<HitStop>,
+    mut time: ResMut<Time<Virtual>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let mut out_of_lives = false;
+    for crash in crashes.read() {
+        if settings.0.shake_strength > 0.0 {
+            trauma.0 = (trauma.0 + CRASH_TRAUMA).min(1.0);
+        }
+        out_of_lives |= crash.lives_left == 0;
+    }
+    if !out_of_lives || !player_query.iter().all(|player| player.crashed) {
+        return;
+    }
+
+    // 联机时游戏时间由锁步控制，不做定格
+    if settings.0.hit_stop_secs > 0.0 && net_session.is_none() {
+        hit_stop.0 = Some(settings.0.hit_stop_secs);
+        time.pause();
+    } else {
+        next_state.set(GameState::GameOver);
+    }
+}
+
+fn tick_hit_stop(
+    real_time: Res<Time<Real>>,
+    mut hit_stop: ResMut<HitStop>,
+    mut time: ResMut<Time<Virtual>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(remaining) = hit_stop.0.as_mut() else {
+        return;
+    };
+    *remaining -= real_time.delta_secs();
+    if *remaining <= 0.0 {
+        hit_stop.0 = None;
+        time.unpause();
+        next_state.set(GameState::GameOver);
+    }
+}
+
+fn shake_camera(
+    real_time: Res<Time<Real>>,
+    settings: Res<UserSettings>,
+    mut trauma: ResMut<Trauma>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+) {
+    if trauma.0 <= 0.0 && !trauma.is_changed() {
+        return;
+    }
+
+    let mut rng = rand::rng();
+    let shake = trauma.0 * trauma.0 * settings.0.shake_strength;
+    for mut transform in camera_query.iter_mut() {
+        transform.translation.x = MAX_OFFSET * shake * rng.random_range(-1.0..=1.0);
+        transform.translation.y = MAX_OFFSET * shake * rng.random_range(-1.0..=1.0);
+        transform.rotation = Quat::from_rotation_z(MAX_ANGLE * shake * rng.random_range(-1.0..=1.0));
+    }
+    // 归零后的这一帧把镜头摆回原位，之后不再处理
+    if trauma.0 > 0.0 {
+        trauma.0 = (trauma.0 - TRAUMA_DECAY * real_time.delta_secs()).max(0.0);
+    }
+}
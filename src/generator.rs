@@ -0,0 +1,225 @@
+// 公平的障碍物生成器
+// 根据 GRAVITY、JUMP_SPEED 和世界速度算出一次起跳能越过的范围，只在必要时缩小成簇数量、拉长间隔，
+// 保证完美操作的玩家能跳过生成的每一段障碍物；随机数的消耗顺序与按难度分段直接生成时一致
+
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+use crate::difficulty::DifficultyCurve;
+use crate::sim::{
+    CACTUS_CONFIGS, COLLISION_THRESHOLD, GRAVITY, GROUND_Y, JUMP_SPEED, OBSTACLE_SPAWN_X, PLAYER_GROUND_Y, PLAYER_X,
+};
+
+// 留给帧率离散和玩家反应的余量（秒）
+pub const SAFETY_MARGIN: f32 = 0.1;
+// 刚生成的障碍物经过恐龙之前，分数最多还会增加这么多，期间可能进入的难度分段都要考虑
+const SCORE_LOOKAHEAD: u32 = 16;
+// 障碍物从生成位置滚动到碰撞范围边缘的距离（像素）
+const APPROACH: f32 = OBSTACLE_SPAWN_X - PLAYER_X - COLLISION_THRESHOLD;
+
+// 一次生成的结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pattern {
+    pub cluster: Option<(usize, u32)>, // (CACTUS_CONFIGS 下标, 并排数量)，None 表示这次不生成
+    pub interval: f32,                 // 到下一次生成的时间（秒）
+}
+
+// 越过高度为 height 的仙人掌时，恐龙中心需要比站立时高出多少
+pub fn clearance(height: f32) -> f32 {
+    (GROUND_Y + height * 0.5 + COLLISION_THRESHOLD - PLAYER_GROUND_Y).max(0.0)
+}
+
+// 从起跳算起，恐龙高出站立位置 clearance 的时间段（秒）；跳不到这个高度时返回 None
+pub fn air_window(clearance: f32) -> Option<(f32, f32)> {
+    let discriminant = JUMP_SPEED * JUMP_SPEED + 2.0 * GRAVITY * clearance;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    Some(((JUMP_SPEED - root) / -GRAVITY, (JUMP_SPEED + root) / -GRAVITY))
+}
+
+// 一簇障碍物从进入到离开碰撞范围，世界需要滚动的距离（像素）
+pub fn cluster_span(width: f32, count: u32) -> f32 {
+    width * count.saturating_sub(1) as f32 + 2.0 * COLLISION_THRESHOLD
+}
+
+// 速度为 speed 时一次起跳最多能越过几个并排的该种仙人掌，越慢飞过的距离越短
+pub fn max_cluster(speed: f32, cactus: usize) -> u32 {
+    let (width, height) = CACTUS_CONFIGS[cactus];
+    let Some((rise, fall)) = air_window(clearance(height)) else {
+        return 0;
+    };
+    let reach = (fall - rise - SAFETY_MARGIN) * speed - 2.0 * COLLISION_THRESHOLD;
+    if reach < 0.0 {
+        0
+    } else {
+        (reach / width) as u32 + 1
+    }
+}
+
+// 所有仙人掌里起跳后最晚越过安全高度的时间，下一簇是什么还不知道时按最坏情况算
+fn slowest_rise() -> f32 {
+    CACTUS_CONFIGS
+        .iter()
+        .filter_map(|&(_, height)| air_window(clearance(height)))
+        .map(|(rise, _)| rise)
+        .fold(0.0, f32::max)
+}
+
+// 这一簇障碍物经过恐龙之前世界速度可能的最小值和最大值
+fn speed_range(curve: &DifficultyCurve, score: u32) -> (f32, f32) {
+    let current = curve.band(score).speed;
+    curve
+        .bands
+        .iter()
+        .filter(|band| band.from > score && band.from <= score + SCORE_LOOKAHEAD)
+        .fold((current, current), |(slow, fast), band| (slow.min(band.speed), fast.max(band.speed)))
+}
+
+// 跟踪完美操作的玩家在已生成的障碍物之后最早什么时候能再次起跳
+pub struct FairGenerator {
+    ready: f32, // 相对最近一次生成，世界还要滚动多少像素恐龙才能落地并再次起跳
+}
+
+impl Default for FairGenerator {
+    fn default() -> Self {
+        Self {
+            ready: f32::NEG_INFINITY,
+        }
+    }
+}
+
+impl FairGenerator {
+    // slow_factor 是这一簇经过恐龙之前世界速度可能乘上的最小倍率（慢动作），没有时为 1
+    pub fn next(&mut self, rng: &mut StdRng, curve: &DifficultyCurve, score: u32, slow_factor: f32) -> Pattern {
+        let band = curve.band(score);
+        // 速度越慢一次起跳飞过的距离越短，越快落地前滚过的距离越长，两头分别按最坏情况算
+        let (slow, fast) = speed_range(curve, score);
+
+        let mut cluster = None;
+        if rng.random_bool(band.chance) {
+            let cactus = band.types[rng.random_range(0..band.types.len())];
+            let count = rng.random_range(band.cluster.0..=band.cluster.1);
+            // 慢动作只放慢世界、不放慢跳跃，飞过的距离跟着缩短；生成间隔的计时同样放慢，障碍物间距不变
+            let count = count.min(max_cluster(slow * slow_factor, cactus));
+            if count > 0 {
+                cluster = Some((cactus, count));
+            }
+        }
+        let interval = rng.random_range(band.gap.0..=band.gap.1);
+
+        // 尽早起跳才能尽早落地：在离开碰撞范围前刚好越过安全高度时起跳，但不能早于上一次落地
+        let mut landing = self.ready;
+        if let Some((cactus, count)) = cluster {
+            let (width, height) = CACTUS_CONFIGS[cactus];
+            let (rise, fall) = air_window(clearance(height)).unwrap_or_default();
+            let end = APPROACH + cluster_span(width, count);
+            let jump = self.ready.max(end - fall * fast);
+            landing = jump + (rise + fall) * fast;
+        }
+
+        // 下一簇障碍物进入碰撞范围之前，恐龙必须已经落地并来得及再次跳起
+        let min_gap = landing - APPROACH + (slowest_rise() + SAFETY_MARGIN) * fast;
+        let interval = interval.max(min_gap / slow);
+        self.ready = landing - interval * slow;
+
+        Pattern { cluster, interval }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difficulty::{Difficulty, DifficultyPresets};
+    use crate::neuro::SIM_DT;
+    use crate::powerup::PowerUpTable;
+    use crate::sim::Simulation;
+
+    const SEEDS: u64 = 2000;
+    const RUN_SECS: f32 = 90.0;
+    // 离散步长让实际跳跃弧线略低于解析解，自动驾驶起跳时留出一点余量
+    const AUTOPILOT_SLACK: f32 = 0.03;
+
+    // 完美玩家：对前方第一组（碰撞范围相互重叠的）障碍物，在最早能越过整组的时刻起跳
+    fn autopilot(sim: &Simulation) -> bool {
+        let mut ahead: Vec<(f32, f32)> = sim
+            .obstacles
+            .iter()
+            .filter(|o| o.x + COLLISION_THRESHOLD > PLAYER_X)
+            .map(|o| (o.x, o.height))
+            .collect();
+        ahead.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let Some(&(first_x, first_height)) = ahead.first() else {
+            return false;
+        };
+
+        let (mut end_x, mut tallest) = (first_x, first_height);
+        for &(x, height) in &ahead[1..] {
+            if x - end_x >= 2.0 * COLLISION_THRESHOLD {
+                break;
+            }
+            end_x = x;
+            tallest = tallest.max(height);
+        }
+
+        let (_, fall) = air_window(clearance(tallest)).unwrap();
+        let time_to_end = (end_x + COLLISION_THRESHOLD - PLAYER_X) / sim.speed();
+        time_to_end <= fall - AUTOPILOT_SLACK
+    }
+
+    fn assert_clearable(name: &str, curve: &DifficultyCurve) {
+        for seed in 0..SEEDS {
+            let mut sim = Simulation::with_curve(seed, curve.clone());
+            while sim.elapsed < RUN_SECS {
+                let jump = autopilot(&sim);
+                sim.step(SIM_DT, jump);
+                assert!(
+                    !sim.crashed,
+                    "{} 曲线的种子 {} 在 {:.2} 秒（{} 分）处无法通过",
+                    name, seed, sim.elapsed, sim.score
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn every_cluster_fits_in_one_jump() {
+        for speed in [200.0, 260.0, 300.0, 430.0] {
+            for cactus in 0..CACTUS_CONFIGS.len() {
+                assert!(max_cluster(speed, cactus) >= 1);
+            }
+        }
+    }
+
+    #[test]
+    fn slow_mo_shrinks_clusters_to_fit_the_slower_world() {
+        let curve = DifficultyCurve::default();
+        let slowest = curve.bands.iter().map(|band| band.speed).fold(f32::INFINITY, f32::min);
+        let factor = PowerUpTable::default().slow_factor;
+        for cactus in 0..CACTUS_CONFIGS.len() {
+            assert!(max_cluster(slowest * factor, cactus) >= 1);
+        }
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut generator = FairGenerator::default();
+        for score in 0..2000 {
+            if let Some((cactus, count)) = generator.next(&mut rng, &curve, score / 4, factor).cluster {
+                assert!(count <= max_cluster(slowest * factor, cactus), "{} 分时 {} 个并排", score / 4, count);
+            }
+        }
+    }
+
+    #[test]
+    fn autopilot_clears_default_curve() {
+        assert_clearable("默认", &DifficultyCurve::default());
+    }
+
+    #[test]
+    fn autopilot_clears_bundled_presets() {
+        let presets = DifficultyPresets::from_text(include_str!("../assets/data/difficulty.txt")).unwrap();
+        for difficulty in Difficulty::ALL {
+            assert_clearable(difficulty.name(), presets.curve(difficulty));
+        }
+    }
+}
@@ -0,0 +1,211 @@
+// 幽灵赛跑：录制每一局的输入，回放最佳记录的半透明恐龙与玩家同场竞技
+// 幽灵带有 Player 组件以复用重力和动画，但碰撞、计分和输入系统都会排除 Ghost
+
+use bevy::prelude::*;
+use dino_game::replay::RunRecording;
+use dino_game::sim::{GROUND_Y, JUMP_SPEED, PLAYER_X};
+use dino_game::storage;
+use rand::prelude::*;
+
+use crate::challenge::DailyChallenge;
+use crate::courses::CourseMode;
+use crate::netplay::NetSession;
+use crate::skin::UserSettings;
+use crate::{current_difficulty, DinoAnimation, DinoClip, GameAssets, GameRng, GameScore, GameState, Lane, Player, WorldSpeed};
+
+const BEST_RUN_FILE: &str = "best_run.txt";
+
+// 距离单位换算，HUD 上以“米”显示
+const PIXELS_PER_METER: f32 = 10.0;
+
+#[derive(Component)]
+pub struct Ghost {
+    next_jump: usize, // 下一个待回放的起跳下标
+}
+
+#[derive(Component)]
+pub struct GhostDeltaText;
+
+// 当前这一局的录像
+#[derive(Resource, Default)]
+pub struct CurrentRun(pub RunRecording);
+
+// 历史最远的一局
+#[derive(Resource, Default)]
+pub struct BestRun(pub Option<RunRecording>);
+
+#[derive(Resource)]
+struct GhostRace {
+    enabled: bool,
+    needs_spawn: bool,
+}
+
+pub struct GhostPlugin {
+    pub enabled: bool, // 是否回放幽灵
+    pub record: bool,  // 是否保存最佳录像
+}
+
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut App) {
+        let best_run = storage::read_file(BEST_RUN_FILE)
+            .ok()
+            .and_then(|content| RunRecording::from_text(&content).ok());
+
+        let record = self.record;
+        app.insert_resource(CurrentRun::default())
+            .insert_resource(BestRun(best_run))
+            .insert_resource(GhostRace {
+                enabled: self.enabled,
+                needs_spawn: false,
+            })
+            .add_systems(OnEnter(GameState::Playing), start_run)
+            // 关卡模式的障碍物与种子无关，不录最佳记录也不回放幽灵
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                save_best_run.run_if(move |mode: Res<CourseMode>| record && mode.selected.is_none()),
+            )
+            .add_systems(
+                Update,
+                (
+                    track_run,
+                    spawn_ghost.run_if(|race: Res<GhostRace>| race.needs_spawn),
+                    replay_ghost,
+                    update_ghost_delta,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+// 每局开始时选定种子：每日挑战使用当天的种子，幽灵赛跑使用同难度最佳记录的种子，否则随机；
+// 每日挑战时只有最佳记录恰好是当天的挑战才回放幽灵
+fn start_run(
+    mut rng: ResMut<GameRng>,
+    mut current_run: ResMut<CurrentRun>,
+    mut race: ResMut<GhostRace>,
+    best_run: Res<BestRun>,
+    settings: Res<UserSettings>,
+    course_mode: Res<CourseMode>,
+    daily: Res<DailyChallenge>,
+    net_session: Option<Res<NetSession>>,
+) {
+    let difficulty = current_difficulty(&settings.0, net_session.is_some() || daily.active);
+    let daily_seed = daily.active.then(|| daily.date.seed());
+    let ghost_seed = best_run
+        .0
+        .as_ref()
+        .filter(|best| race.enabled && course_mode.selected.is_none() && best.difficulty == difficulty)
+        .filter(|best| daily_seed.is_none_or(|seed| seed == best.seed))
+        .map(|best| best.seed);
+    let seed = daily_seed.or(ghost_seed).unwrap_or_else(|| rand::rng().random());
+
+    rng.0 = StdRng::seed_from_u64(seed);
+    current_run.0 = RunRecording::new(seed, difficulty);
+    race.needs_spawn = ghost_seed.is_some();
+}
+
+fn track_run(time: Res<Time>, speed: Res<WorldSpeed>, mut current_run: ResMut<CurrentRun>) {
+    let dt = time.delta_secs();
+    current_run.0.advance(dt, speed.0 * dt);
+}
+
+fn spawn_ghost(mut commands: Commands, mut race: ResMut<GhostRace>, assets: Res<GameAssets>) {
+    race.needs_spawn = false;
+
+    commands.spawn((
+        Sprite {
+            color: Color::srgba(1.0, 1.0, 1.0, 0.35), // 半透明
+            ..assets.dino_sprite(DinoClip::Run)
+        },
+        Transform::from_xyz(PLAYER_X, GROUND_Y + 30.0, 0.5), // 画在玩家后面
+        Player {
+            velocity_y: 0.0,
+            is_jumping: false,
+            jump_cooldown: 0.0,
+            crashed: false,
+            ducking: false,
+        },
+        Lane(0),
+        Ghost { next_jump: 0 },
+        DinoAnimation::new(DinoClip::Run),
+    ));
+
+    commands.spawn((
+        GhostDeltaText,
+        Text2d::new(""),
+        Transform::from_xyz(-330.0, 120.0, 1.0),
+        TextFont {
+            font_size: 18.0,
+            ..default()
+        },
+    ));
+}
+
+// 按录像时间触发起跳，并根据两者的距离差摆放幽灵
+fn replay_ghost(
+    current_run: Res<CurrentRun>,
+    best_run: Res<BestRun>,
+    mut ghost_query: Query<(&mut Ghost, &mut Player, &mut Transform)>,
+) {
+    let Some(best) = best_run.0.as_ref() else {
+        return;
+    };
+    let time = current_run.0.duration;
+
+    for (mut ghost, mut player, mut transform) in ghost_query.iter_mut() {
+        while let Some(&jump_time) = best.jumps.get(ghost.next_jump) {
+            if jump_time > time {
+                break;
+            }
+            // 录像里的起跳都是当时合法的，包括二段跳，直接照做
+            player.velocity_y = JUMP_SPEED;
+            player.is_jumping = true;
+            ghost.next_jump += 1;
+        }
+
+        transform.translation.x = PLAYER_X + best.distance_at(time) - current_run.0.distance;
+    }
+}
+
+fn update_ghost_delta(
+    current_run: Res<CurrentRun>,
+    best_run: Res<BestRun>,
+    mut text_query: Query<(&mut Text2d, &mut TextColor), With<GhostDeltaText>>,
+) {
+    let Some(best) = best_run.0.as_ref() else {
+        return;
+    };
+    let delta = (current_run.0.distance - best.distance_at(current_run.0.duration)) / PIXELS_PER_METER;
+
+    for (mut text, mut color) in text_query.iter_mut() {
+        text.0 = format!("Ghost {:+.0}m", delta);
+        color.0 = if delta >= 0.0 {
+            Color::srgb(0.1, 0.6, 0.1) // 领先为绿色
+        } else {
+            Color::srgb(0.8, 0.1, 0.1) // 落后为红色
+        };
+    }
+}
+
+// 跑得比历史记录更远时保存录像
+fn save_best_run(
+    mut current_run: ResMut<CurrentRun>,
+    mut best_run: ResMut<BestRun>,
+    score_query: Query<&GameScore>,
+) {
+    if let Ok(score) = score_query.single() {
+        current_run.0.score = score.value;
+    }
+
+    let is_best = best_run
+        .0
+        .as_ref()
+        .is_none_or(|best| current_run.0.distance > best.distance);
+    if is_best {
+        if let Err(e) = storage::write_file(BEST_RUN_FILE, &current_run.0.to_text()) {
+            warn!("保存最佳录像失败: {}", e);
+        }
+        best_run.0 = Some(current_run.0.clone());
+    }
+}
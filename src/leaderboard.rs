@@ -0,0 +1,291 @@
+// 本地排行榜：按模式、计分方式和难度分榜，每榜保留前 10 名
+// 保存在存档目录的 leaderboard.txt 中，可以导出为 CSV 和 JSON
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io;
+
+use crate::daily::Date;
+use crate::storage;
+
+pub const BOARD_SIZE: usize = 10;
+pub const NAME_MAX_CHARS: usize = 12;
+const LEADERBOARD_HEADER: &str = "dino-leaderboard v1";
+const LEADERBOARD_FILE: &str = "leaderboard.txt";
+pub const CSV_FILE: &str = "leaderboard.csv";
+pub const JSON_FILE: &str = "leaderboard.json";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub name: String,
+    pub score: u32,
+    pub date: Date,
+}
+
+// 榜单名形如 endless/obstacles/normal，由调用方拼出
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Leaderboard {
+    boards: BTreeMap<String, Vec<Entry>>,
+}
+
+// 去掉控制字符和首尾空白，限制长度
+pub fn clean_name(name: &str) -> String {
+    name.trim()
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(NAME_MAX_CHARS)
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+impl Leaderboard {
+    // 文件不存在或损坏时从空榜开始
+    pub fn load() -> Leaderboard {
+        storage::read_file(LEADERBOARD_FILE)
+            .ok()
+            .and_then(|content| Leaderboard::from_text(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        storage::write_file(LEADERBOARD_FILE, &self.to_text())
+    }
+
+    pub fn board(&self, category: &str) -> &[Entry] {
+        self.boards.get(category).map_or(&[], Vec::as_slice)
+    }
+
+    // 有记录的全部榜单名，按字母顺序
+    pub fn categories(&self) -> impl Iterator<Item = &str> {
+        self.boards.keys().map(String::as_str)
+    }
+
+    // 分数能否进入该榜前 10 名
+    pub fn qualifies(&self, category: &str, score: u32) -> bool {
+        let board = self.board(category);
+        score > 0 && (board.len() < BOARD_SIZE || board.last().is_some_and(|last| score > last.score))
+    }
+
+    // 插入一条记录并返回名次（从 1 开始）；同分时先上榜的排在前面
+    pub fn insert(&mut self, category: &str, entry: Entry) -> Option<usize> {
+        if !self.qualifies(category, entry.score) {
+            return None;
+        }
+        let board = self.boards.entry(category.to_string()).or_default();
+        let rank = board
+            .iter()
+            .take_while(|existing| existing.score >= entry.score)
+            .count();
+        board.insert(rank, entry);
+        board.truncate(BOARD_SIZE);
+        Some(rank + 1)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut content = format!("{}\n", LEADERBOARD_HEADER);
+        for (category, board) in &self.boards {
+            let _ = writeln!(content, "board {}", category);
+            for entry in board {
+                let _ = writeln!(content, "entry {} {} {}", entry.score, entry.date, entry.name);
+            }
+        }
+        content
+    }
+
+    pub fn from_text(content: &str) -> io::Result<Leaderboard> {
+        let mut lines = content.lines();
+        if lines.next().map(str::trim) != Some(LEADERBOARD_HEADER) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "排行榜文件头不匹配"));
+        }
+
+        let mut leaderboard = Leaderboard::default();
+        let mut current: Option<String> = None;
+        for line in lines {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "board" => current = Some(value.trim().to_string()),
+                "entry" => {
+                    let mut fields = value.splitn(3, ' ');
+                    let score = fields.next().and_then(|score| score.parse().ok());
+                    let date = fields.next().and_then(Date::parse);
+                    let name = clean_name(fields.next().unwrap_or(""));
+                    if let (Some(category), Some(score), Some(date)) = (&current, score, date) {
+                        leaderboard
+                            .boards
+                            .entry(category.clone())
+                            .or_default()
+                            .push(Entry { name, score, date });
+                    }
+                }
+                _ => {}
+            }
+        }
+        for board in leaderboard.boards.values_mut() {
+            board.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+            board.truncate(BOARD_SIZE);
+        }
+        Ok(leaderboard)
+    }
+
+    // 每行一条记录，名字按 CSV 规则加引号
+    pub fn to_csv(&self) -> String {
+        let mut content = String::from("board,rank,name,score,date\n");
+        for (category, board) in &self.boards {
+            for (rank, entry) in board.iter().enumerate() {
+                let name = format!("\"{}\"", entry.name.replace('"', "\"\""));
+                let _ = writeln!(
+                    content,
+                    "{},{},{},{},{}",
+                    category,
+                    rank + 1,
+                    name,
+                    entry.score,
+                    entry.date
+                );
+            }
+        }
+        content
+    }
+
+    // 以榜单名为键、记录数组为值的 JSON 对象
+    pub fn to_json(&self) -> String {
+        let boards: Vec<String> = self
+            .boards
+            .iter()
+            .map(|(category, board)| {
+                let entries: Vec<String> = board
+                    .iter()
+                    .enumerate()
+                    .map(|(rank, entry)| {
+                        format!(
+                            "    {{\"rank\": {}, \"name\": {}, \"score\": {}, \"date\": \"{}\"}}",
+                            rank + 1,
+                            json_string(&entry.name),
+                            entry.score,
+                            entry.date
+                        )
+                    })
+                    .collect();
+                format!("  {}: [\n{}\n  ]", json_string(category), entries.join(",\n"))
+            })
+            .collect();
+        format!("{{\n{}\n}}\n", boards.join(",\n"))
+    }
+
+    // 导出到存档目录，返回写入的文件名
+    pub fn export(&self) -> io::Result<[&'static str; 2]> {
+        storage::write_file(CSV_FILE, &self.to_csv())?;
+        storage::write_file(JSON_FILE, &self.to_json())?;
+        Ok([CSV_FILE, JSON_FILE])
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOARD: &str = "endless/obstacles/normal/lives-1";
+
+    fn entry(name: &str, score: u32) -> Entry {
+        Entry {
+            name: name.to_string(),
+            score,
+            date: Date::from_days(19_723),
+        }
+    }
+
+    fn names(leaderboard: &Leaderboard) -> Vec<&str> {
+        leaderboard.board(BOARD).iter().map(|entry| entry.name.as_str()).collect()
+    }
+
+    #[test]
+    fn ties_rank_earlier_entries_first() {
+        let mut leaderboard = Leaderboard::default();
+        assert_eq!(leaderboard.insert(BOARD, entry("a", 5)), Some(1));
+        assert_eq!(leaderboard.insert(BOARD, entry("b", 5)), Some(2));
+        assert_eq!(leaderboard.insert(BOARD, entry("c", 8)), Some(1));
+        assert_eq!(leaderboard.insert(BOARD, entry("d", 5)), Some(4));
+        assert_eq!(names(&leaderboard), ["c", "a", "b", "d"]);
+        assert_eq!(leaderboard.insert(BOARD, entry("zero", 0)), None);
+    }
+
+    #[test]
+    fn full_board_truncates_and_rejects_ties_with_last() {
+        let mut leaderboard = Leaderboard::default();
+        for score in 1..=BOARD_SIZE as u32 {
+            leaderboard.insert(BOARD, entry(&score.to_string(), score));
+        }
+        assert!(!leaderboard.qualifies(BOARD, 1));
+        assert_eq!(leaderboard.insert(BOARD, entry("tie", 1)), None);
+        assert_eq!(leaderboard.insert(BOARD, entry("new", 2)), Some(BOARD_SIZE));
+        assert_eq!(leaderboard.board(BOARD).len(), BOARD_SIZE);
+        assert_eq!(leaderboard.board(BOARD).last().unwrap().name, "new");
+        // 其它榜单不受影响
+        assert!(leaderboard.qualifies("daily/obstacles/normal/lives-1", 1));
+    }
+
+    #[test]
+    fn lives_settings_rank_on_separate_boards() {
+        // 多几条命更容易拿高分，命数不同的成绩分开排名
+        let mut leaderboard = Leaderboard::default();
+        leaderboard.insert("endless/obstacles/normal/lives-3", entry("lives", 50));
+        assert_eq!(leaderboard.insert(BOARD, entry("one", 5)), Some(1));
+        assert_eq!(names(&leaderboard), ["one"]);
+        assert_eq!(leaderboard.categories().collect::<Vec<_>>(), [BOARD, "endless/obstacles/normal/lives-3"]);
+    }
+
+    #[test]
+    fn text_round_trip_keeps_names_with_spaces() {
+        let mut leaderboard = Leaderboard::default();
+        leaderboard.insert(BOARD, entry("Dino Fan", 12));
+        leaderboard.insert(BOARD, entry("x", 3));
+        leaderboard.insert("course/01_tutorial/obstacles/lives-3", entry("恐龙", 7));
+        assert_eq!(Leaderboard::from_text(&leaderboard.to_text()).unwrap(), leaderboard);
+        assert!(Leaderboard::from_text("dino-leaderboard v2\n").is_err());
+    }
+
+    #[test]
+    fn clean_name_strips_control_characters_and_limits_length() {
+        assert_eq!(clean_name("  a\tb\u{7}c  "), "abc");
+        assert_eq!(clean_name("abcdefghijklmnop").chars().count(), NAME_MAX_CHARS);
+        assert_eq!(clean_name("abcdefghijk  z"), "abcdefghijk");
+    }
+
+    #[test]
+    fn csv_quotes_names() {
+        let mut leaderboard = Leaderboard::default();
+        leaderboard.insert(BOARD, entry("a,\"b\"", 4));
+        assert_eq!(
+            leaderboard.to_csv(),
+            "board,rank,name,score,date\nendless/obstacles/normal/lives-1,1,\"a,\"\"b\"\"\",4,2024-01-01\n"
+        );
+    }
+
+    #[test]
+    fn json_escapes_quotes_backslashes_and_control_characters() {
+        assert_eq!(json_string("a\"b\\c\u{1}"), "\"a\\\"b\\\\c\\u0001\"");
+        let mut leaderboard = Leaderboard::default();
+        leaderboard.insert(BOARD, entry("q\"", 4));
+        assert_eq!(
+            leaderboard.to_json(),
+            "{\n  \"endless/obstacles/normal/lives-1\": [\n    {\"rank\": 1, \"name\": \"q\\\"\", \"score\": 4, \"date\": \"2024-01-01\"}\n  ]\n}\n"
+        );
+    }
+}
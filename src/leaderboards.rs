@@ -0,0 +1,329 @@
+// 排行榜：单人局的分数进入当前榜单前 10 名时，在游戏结束画面输入名字上榜
+// 菜单里按 V 查看各个榜单，←/→ 切换榜单，X 导出为 CSV 和 JSON
+
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use dino_game::daily::Date;
+use dino_game::leaderboard::{clean_name, Entry, Leaderboard, BOARD_SIZE, NAME_MAX_CHARS};
+
+use crate::achievements::{solo_run, PLAYER};
+use crate::challenge::DailyChallenge;
+use crate::courses::CourseMode;
+use crate::menu::{main_page, MenuPage};
+use crate::skin::UserSettings;
+use crate::{daynight, GameScore, GameState, Lane};
+
+#[derive(Resource)]
+pub struct Leaderboards {
+    board: Leaderboard,
+    viewing: Option<String>, // 菜单里正在查看的榜单
+    status: String,          // 导出结果
+}
+
+// 游戏结束画面上正在输入的名字
+#[derive(Resource, Default)]
+pub struct NameEntry {
+    pending: Option<(String, u32)>, // 榜单名和分数
+    name: String,
+}
+
+// 输入名字时，游戏结束画面的其他快捷键暂停生效
+pub fn typing(entry: Res<NameEntry>) -> bool {
+    entry.pending.is_some()
+}
+
+#[derive(Component)]
+struct NameEntryText;
+
+#[derive(Component)]
+struct LeaderboardText;
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Leaderboards {
+            board: Leaderboard::load(),
+            viewing: None,
+            status: String::new(),
+        })
+        .init_resource::<NameEntry>()
+        .add_systems(OnEnter(GameState::GameOver), start_name_entry.run_if(solo_run))
+        .add_systems(OnExit(GameState::GameOver), clear_name_entry)
+        .add_systems(Update, type_name.run_if(in_state(GameState::GameOver).and(typing)))
+        .add_systems(
+            Update,
+            (
+                toggle_leaderboard,
+                browse_leaderboard.run_if(not(main_page)),
+                draw_leaderboard,
+            )
+                .chain()
+                .run_if(in_state(GameState::Menu)),
+        )
+        .add_systems(OnExit(GameState::Menu), despawn_leaderboard);
+    }
+}
+
+// 当前模式对应的榜单名：关卡按关卡分榜，无尽模式和每日挑战再按难度分榜；
+// 计分方式和每局的命数都会影响分数，同样分榜
+fn category(settings: &UserSettings, course_mode: &CourseMode, daily: &DailyChallenge) -> String {
+    let score_mode = crate::current_score_mode(&settings.0, daily.active).name();
+    let lives = crate::lives_per_run(&settings.0, daily.active);
+    match course_mode.course() {
+        Some(course) => format!("course/{}/{}/lives-{}", course.id, score_mode, lives),
+        None => {
+            let mode = if daily.active { "daily" } else { "endless" };
+            let difficulty = crate::current_difficulty(&settings.0, daily.active);
+            format!("{}/{}/{}/lives-{}", mode, score_mode, difficulty.name(), lives)
+        }
+    }
+}
+
+// 只记录本地单人局；联机、双人模式和 AI 代打不上榜
+fn start_name_entry(
+    mut commands: Commands,
+    settings: Res<UserSettings>,
+    course_mode: Res<CourseMode>,
+    daily: Res<DailyChallenge>,
+    leaderboards: Res<Leaderboards>,
+    mut entry: ResMut<NameEntry>,
+    score_query: Query<(&GameScore, &Lane)>,
+) {
+    let Some((score, _)) = score_query.iter().find(|(_, lane)| **lane == PLAYER) else {
+        return;
+    };
+    let score = score.value;
+    let category = category(&settings, &course_mode, &daily);
+    if !leaderboards.board.qualifies(&category, score) {
+        return;
+    }
+
+    entry.name = settings.0.player_name.clone();
+    entry.pending = Some((category, score));
+    commands.spawn((
+        NameEntryText,
+        daynight::HudText,
+        Text2d::new(name_prompt(&entry.name)),
+        Transform::from_xyz(0.0, -90.0, 1.0),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+    ));
+}
+
+fn name_prompt(name: &str) -> String {
+    format!("New high score! Enter your name: {}_\nENTER: save  ESC: skip", name)
+}
+
+// Enter 保存，ESC 放弃上榜；用过的 ESC 不再传给回到菜单的快捷键
+fn type_name(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut entry: ResMut<NameEntry>,
+    mut leaderboards: ResMut<Leaderboards>,
+    mut settings: ResMut<UserSettings>,
+    mut text_query: Query<&mut Text2d, With<NameEntryText>>,
+) {
+    let mut result = None;
+    for event in keyboard_events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Enter => result = Some(true),
+            Key::Escape => result = Some(false),
+            Key::Backspace => {
+                entry.name.pop();
+            }
+            Key::Space if entry.name.chars().count() < NAME_MAX_CHARS => entry.name.push(' '),
+            Key::Character(text) => {
+                for c in text.chars().filter(|c| !c.is_control()) {
+                    if entry.name.chars().count() < NAME_MAX_CHARS {
+                        entry.name.push(c);
+                    }
+                }
+            }
+            _ => {}
+        }
+        if result.is_some() {
+            break;
+        }
+    }
+
+    let message = match result {
+        None => name_prompt(&entry.name),
+        Some(false) => {
+            keyboard_input.clear_just_pressed(KeyCode::Escape);
+            entry.pending = None;
+            String::new()
+        }
+        Some(true) => {
+            let Some((category, score)) = entry.pending.take() else {
+                return;
+            };
+            let name = match clean_name(&entry.name) {
+                name if name.is_empty() => "Player".to_string(),
+                name => name,
+            };
+            let date = Date::today(settings.0.utc_offset);
+            let rank = leaderboards.board.insert(
+                &category,
+                Entry {
+                    name: name.clone(),
+                    score,
+                    date,
+                },
+            );
+            if let Err(e) = leaderboards.board.save() {
+                warn!("保存排行榜失败: {}", e);
+            }
+            if settings.0.player_name != name {
+                settings.0.player_name = name.clone();
+                if let Err(e) = settings.0.save() {
+                    warn!("保存设置失败: {}", e);
+                }
+            }
+            rank.map_or(String::new(), |rank| format!("#{} {} - {}", rank, name, category))
+        }
+    };
+    for mut text in text_query.iter_mut() {
+        if text.0 != message {
+            text.0 = message.clone();
+        }
+    }
+}
+
+fn clear_name_entry(mut commands: Commands, mut entry: ResMut<NameEntry>, query: Query<Entity, With<NameEntryText>>) {
+    entry.pending = None;
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+// 按 V 打开排行榜页面，默认显示当前模式的榜单；再按 V 返回主菜单
+fn toggle_leaderboard(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<UserSettings>,
+    course_mode: Res<CourseMode>,
+    daily: Res<DailyChallenge>,
+    mut leaderboards: ResMut<Leaderboards>,
+    mut page: ResMut<MenuPage>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyV) {
+        return;
+    }
+    if *page == MenuPage::Leaderboard {
+        *page = MenuPage::Main;
+    } else {
+        leaderboards.viewing = Some(category(&settings, &course_mode, &daily));
+        leaderboards.status.clear();
+        *page = MenuPage::Leaderboard;
+    }
+}
+
+// ←/→ 在有记录的榜单之间切换，X 导出
+fn browse_leaderboard(keyboard_input: Res<ButtonInput<KeyCode>>, mut leaderboards: ResMut<Leaderboards>) {
+    let step: isize = if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        1
+    } else if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        -1
+    } else {
+        0
+    };
+    if step != 0 {
+        let categories = category_list(&leaderboards);
+        let current = categories
+            .iter()
+            .position(|category| Some(category) == leaderboards.viewing.as_ref())
+            .unwrap_or(0);
+        let next = (current as isize + step).rem_euclid(categories.len() as isize) as usize;
+        leaderboards.viewing = Some(categories[next].clone());
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyX) {
+        leaderboards.status = match leaderboards.board.export() {
+            Ok(files) => format!("Exported {} to saves/", files.join(", ")),
+            Err(e) => format!("Export failed: {}", e),
+        };
+    }
+}
+
+// 有记录的榜单，再加上还没有记录的当前榜单
+fn category_list(leaderboards: &Leaderboards) -> Vec<String> {
+    let mut categories: Vec<String> = leaderboards.board.categories().map(str::to_string).collect();
+    if let Some(viewing) = &leaderboards.viewing {
+        if !categories.contains(viewing) {
+            categories.push(viewing.clone());
+            categories.sort();
+        }
+    }
+    categories
+}
+
+fn draw_leaderboard(
+    mut commands: Commands,
+    page: Res<MenuPage>,
+    leaderboards: Res<Leaderboards>,
+    mut query: Query<(Entity, &mut Text2d), With<LeaderboardText>>,
+) {
+    if *page != MenuPage::Leaderboard {
+        for (entity, _) in query.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    let viewing = leaderboards.viewing.clone().unwrap_or_default();
+    let categories = category_list(&leaderboards);
+    let index = categories
+        .iter()
+        .position(|category| *category == viewing)
+        .map_or(0, |index| index + 1);
+    let mut text = format!("LEADERBOARD\n< {} >  ({}/{})\n\n", viewing, index, categories.len());
+    let board = leaderboards.board.board(&viewing);
+    for rank in 0..BOARD_SIZE {
+        match board.get(rank) {
+            Some(entry) => text.push_str(&format!(
+                "{:>2}. {:<12}  {:>6}  {}\n",
+                rank + 1,
+                entry.name,
+                entry.score,
+                entry.date
+            )),
+            None => text.push_str(&format!("{:>2}. ---\n", rank + 1)),
+        }
+    }
+    text.push_str("\n<-/->: board  X: export CSV/JSON  V/ESC: back");
+    if !leaderboards.status.is_empty() {
+        text.push('\n');
+        text.push_str(&leaderboards.status);
+    }
+
+    match query.single_mut() {
+        Ok((_, mut board_text)) => {
+            if board_text.0 != text {
+                board_text.0 = text;
+            }
+        }
+        Err(_) => {
+            commands.spawn((
+                LeaderboardText,
+                daynight::HudText,
+                Text2d::new(text),
+                Transform::from_xyz(0.0, 20.0, 1.0),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+            ));
+        }
+    }
+}
+
+fn despawn_leaderboard(mut commands: Commands, query: Query<Entity, With<LeaderboardText>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
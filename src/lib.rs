@@ -0,0 +1,19 @@
+// 与 Bevy 无关的游戏逻辑，供游戏本体和训练器共用
+pub mod achievement;
+pub mod course;
+pub mod daily;
+pub mod difficulty;
+pub mod generator;
+pub mod leaderboard;
+pub mod lockstep;
+pub mod neuro;
+pub mod powerup;
+pub mod profile;
+pub mod replay;
+pub mod settings;
+pub mod sheet;
+pub mod sim;
+pub mod stats;
+pub mod storage;
+pub mod synth;
+pub mod theme;
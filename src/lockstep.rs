@@ -0,0 +1,418 @@
+// 基于 UDP 的确定性锁步联机
+// 双方只交换每个 tick 的跳跃输入，本地输入延迟 input_delay 个 tick 生效；
+// 每个包都带上对方尚未确认的全部输入，所以丢包和乱序只会造成短暂等待，不会导致不同步
+
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+use rand::prelude::*;
+
+const PROTOCOL_MAGIC: u32 = 0x44_49_4e_4f; // "DINO"
+
+const PACKET_HELLO: u8 = 0;
+const PACKET_WELCOME: u8 = 1;
+const PACKET_INPUT: u8 = 2;
+const PACKET_READY: u8 = 3;
+
+// 单个输入包最多携带的 tick 数（用一个 u64 位图表示）
+const MAX_INPUTS_PER_PACKET: usize = 64;
+
+pub const DEFAULT_INPUT_DELAY: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Host,
+    Client,
+}
+
+pub struct Lockstep {
+    socket: UdpSocket,
+    peer: Option<SocketAddr>,
+    role: Role,
+    seed: Option<u64>,
+    input_delay: u32,
+    round: u32,
+    local_inputs: Vec<bool>,          // 下标为 tick
+    remote_inputs: Vec<Option<bool>>, // 下标为 tick，None 表示还没收到
+    remote_acked: usize,              // 对方已连续收到的本地输入数量
+    next_tick: u32,                   // 下一个要模拟的 tick
+    local_ready: Option<u32>,         // 本地已准备好开始的回合
+    remote_ready: Option<u32>,        // 对方已准备好开始的回合
+    packet_loss: f64,                 // 模拟丢包率，仅用于测试
+    reorder: f64,                     // 模拟乱序的概率，仅用于测试
+    held: Option<Vec<u8>>,            // 为模拟乱序而扣下的包，在下一个包之后发出
+    rng: StdRng,
+}
+
+impl Lockstep {
+    // 主机：监听端口，等待对方连接，种子由主机决定
+    pub fn host(port: u16, seed: u64) -> io::Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", port))?;
+        Self::new(socket, None, Role::Host, Some(seed))
+    }
+
+    // 客户端：连接主机，握手后获得种子
+    pub fn join(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let peer = address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "无效的主机地址"))?;
+        let bind_address: SocketAddr = if peer.is_ipv4() {
+            "0.0.0.0:0".parse().unwrap()
+        } else {
+            "[::]:0".parse().unwrap()
+        };
+        let socket = UdpSocket::bind(bind_address)?;
+        Self::new(socket, Some(peer), Role::Client, None)
+    }
+
+    fn new(socket: UdpSocket, peer: Option<SocketAddr>, role: Role, seed: Option<u64>) -> io::Result<Self> {
+        socket.set_nonblocking(true)?;
+        let mut session = Self {
+            socket,
+            peer,
+            role,
+            seed,
+            input_delay: DEFAULT_INPUT_DELAY,
+            round: 0,
+            local_inputs: Vec::new(),
+            remote_inputs: Vec::new(),
+            remote_acked: 0,
+            next_tick: 0,
+            local_ready: None,
+            remote_ready: None,
+            packet_loss: 0.0,
+            reorder: 0.0,
+            held: None,
+            rng: StdRng::from_os_rng(),
+        };
+        session.reset_round(0);
+        Ok(session)
+    }
+
+    pub fn set_input_delay(&mut self, ticks: u32) {
+        self.input_delay = ticks;
+        self.reset_round(self.round);
+    }
+
+    pub fn set_packet_loss(&mut self, loss: f64) {
+        self.packet_loss = loss.clamp(0.0, 1.0);
+    }
+
+    pub fn set_packet_reorder(&mut self, reorder: f64) {
+        self.reorder = reorder.clamp(0.0, 1.0);
+    }
+
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    // 握手完成后才能开始模拟
+    pub fn is_connected(&self) -> bool {
+        self.peer.is_some() && self.seed.is_some()
+    }
+
+    pub fn round(&self) -> u32 {
+        self.round
+    }
+
+    pub fn next_tick(&self) -> u32 {
+        self.next_tick
+    }
+
+    // 每回合的种子，双方一致
+    pub fn round_seed(&self) -> Option<u64> {
+        self.seed.map(|seed| seed.wrapping_add(self.round as u64))
+    }
+
+    fn reset_round(&mut self, round: u32) {
+        self.round = round;
+        self.next_tick = 0;
+        self.remote_acked = 0;
+        // 前 input_delay 个 tick 双方都没有输入
+        self.local_inputs = vec![false; self.input_delay as usize];
+        self.remote_inputs = vec![Some(false); self.input_delay as usize];
+    }
+
+    // 记录本地输入，它会在 input_delay 个 tick 之后生效；
+    // 返回 false 表示输入缓冲已满（正在等待对方），这次输入需要保留到下一帧
+    pub fn push_local_input(&mut self, jump: bool) -> bool {
+        if self.local_inputs.len() > (self.next_tick + self.input_delay) as usize {
+            return false;
+        }
+        self.local_inputs.push(jump);
+        true
+    }
+
+    // 双方输入都已到齐时推进一个 tick，返回 [0 号玩家, 1 号玩家] 的输入
+    pub fn try_advance(&mut self) -> Option<[bool; 2]> {
+        let tick = self.next_tick as usize;
+        let local = *self.local_inputs.get(tick)?;
+        let remote = (*self.remote_inputs.get(tick)?)?;
+        self.next_tick += 1;
+        Some(match self.role {
+            Role::Host => [local, remote],
+            Role::Client => [remote, local],
+        })
+    }
+
+    // 本地准备开始下一回合；双方都准备好后返回 true 并重置回合
+    pub fn ready_for_next_round(&mut self) -> bool {
+        let next_round = self.round + 1;
+        self.local_ready = Some(next_round);
+        if self.remote_ready.is_some_and(|round| round >= next_round) {
+            self.local_ready = None;
+            self.reset_round(next_round);
+            return true;
+        }
+        false
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.local_ready.is_some()
+    }
+
+    // 收取所有到达的数据包
+    pub fn poll(&mut self) -> io::Result<()> {
+        let mut buffer = [0u8; 64];
+        loop {
+            match self.socket.recv_from(&mut buffer) {
+                Ok((len, from)) => self.handle_packet(&buffer[..len], from),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                // Windows 上对方端口未打开时会收到 ConnectionReset，忽略即可
+                Err(e) if e.kind() == io::ErrorKind::ConnectionReset => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn handle_packet(&mut self, packet: &[u8], from: SocketAddr) {
+        let mut reader = Reader(packet);
+        let Some(kind) = reader.u8() else {
+            return;
+        };
+
+        match (kind, self.role) {
+            (PACKET_HELLO, Role::Host) => {
+                if reader.u32() != Some(PROTOCOL_MAGIC) {
+                    return;
+                }
+                // 只接受第一个连上来的对手
+                if self.peer.is_none() {
+                    self.peer = Some(from);
+                }
+                if self.peer == Some(from) {
+                    if let Some(seed) = self.seed {
+                        let mut reply = vec![PACKET_WELCOME];
+                        reply.extend_from_slice(&PROTOCOL_MAGIC.to_le_bytes());
+                        reply.extend_from_slice(&seed.to_le_bytes());
+                        self.send_raw(&reply);
+                    }
+                }
+            }
+            (PACKET_WELCOME, Role::Client) if Some(from) == self.peer => {
+                if reader.u32() != Some(PROTOCOL_MAGIC) {
+                    return;
+                }
+                if let Some(seed) = reader.u64() {
+                    self.seed.get_or_insert(seed);
+                }
+            }
+            (PACKET_INPUT, _) if Some(from) == self.peer => {
+                let (Some(round), Some(start), Some(count), Some(bits), Some(ack)) =
+                    (reader.u32(), reader.u32(), reader.u8(), reader.u64(), reader.u32())
+                else {
+                    return;
+                };
+                // 对方已经开始了我们正在等待的下一回合，视为对方已准备好
+                if self.local_ready == Some(round) {
+                    self.remote_ready = Some(round);
+                    self.ready_for_next_round();
+                }
+                if round != self.round {
+                    return;
+                }
+
+                // 对方最多比我们多跑 input_delay 个 tick，超出窗口的包一定是损坏或伪造的，
+                // 直接丢弃，避免按对方给的下标无限扩大缓冲
+                let window = self.local_inputs.len() + self.input_delay as usize + MAX_INPUTS_PER_PACKET;
+                if start as usize > window {
+                    return;
+                }
+                self.remote_acked = self.remote_acked.max(ack as usize);
+                for i in 0..count.min(MAX_INPUTS_PER_PACKET as u8) as usize {
+                    let tick = start as usize + i;
+                    if tick >= self.remote_inputs.len() {
+                        self.remote_inputs.resize(tick + 1, None);
+                    }
+                    self.remote_inputs[tick] = Some(bits & (1 << i) != 0);
+                }
+            }
+            (PACKET_READY, _) if Some(from) == self.peer => {
+                if let Some(round) = reader.u32() {
+                    self.remote_ready = Some(self.remote_ready.map_or(round, |r| r.max(round)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // 每帧调用一次：握手阶段发送 Hello，之后发送未确认的输入和准备状态
+    pub fn send(&mut self) {
+        if self.seed.is_none() {
+            if self.role == Role::Client {
+                let mut packet = vec![PACKET_HELLO];
+                packet.extend_from_slice(&PROTOCOL_MAGIC.to_le_bytes());
+                self.send_raw(&packet);
+            }
+            return;
+        }
+        if self.peer.is_none() {
+            return;
+        }
+
+        if let Some(round) = self.local_ready {
+            let mut packet = vec![PACKET_READY];
+            packet.extend_from_slice(&round.to_le_bytes());
+            self.send_raw(&packet);
+        }
+
+        let start = self.remote_acked.min(self.local_inputs.len());
+        let end = self.local_inputs.len().min(start + MAX_INPUTS_PER_PACKET);
+        let mut bits = 0u64;
+        for (i, &jump) in self.local_inputs[start..end].iter().enumerate() {
+            if jump {
+                bits |= 1 << i;
+            }
+        }
+
+        let mut packet = vec![PACKET_INPUT];
+        packet.extend_from_slice(&self.round.to_le_bytes());
+        packet.extend_from_slice(&(start as u32).to_le_bytes());
+        packet.push((end - start) as u8);
+        packet.extend_from_slice(&bits.to_le_bytes());
+        packet.extend_from_slice(&(self.remote_contiguous() as u32).to_le_bytes());
+        self.send_raw(&packet);
+    }
+
+    // 从 0 开始连续收到的对方输入数量，作为确认号发给对方
+    fn remote_contiguous(&self) -> usize {
+        self.remote_inputs
+            .iter()
+            .position(Option::is_none)
+            .unwrap_or(self.remote_inputs.len())
+    }
+
+    fn send_raw(&mut self, packet: &[u8]) {
+        let Some(peer) = self.peer else {
+            return;
+        };
+        if self.packet_loss > 0.0 && self.rng.random_bool(self.packet_loss) {
+            return;
+        }
+        if self.held.is_none() && self.reorder > 0.0 && self.rng.random_bool(self.reorder) {
+            self.held = Some(packet.to_vec());
+            return;
+        }
+        // UDP 发送失败等同于丢包，下一帧会重发
+        let _ = self.socket.send_to(packet, peer);
+        if let Some(held) = self.held.take() {
+            let _ = self.socket.send_to(&held, peer);
+        }
+    }
+}
+
+// 小端字节读取
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        if self.0.len() < N {
+            return None;
+        }
+        let (head, rest) = self.0.split_at(N);
+        self.0 = rest;
+        head.try_into().ok()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take::<1>().map(|b| b[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take().map(u64::from_le_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 两个实例在回环地址上对打：开启丢包和乱序后，双方仍然得到相同的种子和完全一致的输入序列
+    #[test]
+    fn loopback_peers_stay_in_sync_under_loss_and_reordering() {
+        const TICKS: usize = 300;
+        let mut host = Lockstep::host(0, 42).unwrap();
+        let port = host.local_addr().unwrap().port();
+        let mut client = Lockstep::join(("127.0.0.1", port)).unwrap();
+
+        let mut peers = [&mut host, &mut client];
+        for peer in peers.iter_mut() {
+            peer.set_packet_loss(0.3);
+            peer.set_packet_reorder(0.3);
+        }
+
+        let mut streams: [Vec<[bool; 2]>; 2] = [Vec::new(), Vec::new()];
+        let mut pushed = [0usize; 2];
+        for _ in 0..20_000 {
+            for (index, peer) in peers.iter_mut().enumerate() {
+                peer.poll().unwrap();
+                if peer.is_connected() {
+                    // 两边按不同的节奏起跳
+                    let jump = pushed[index] % (5 + index * 2) == 0;
+                    if pushed[index] < TICKS && peer.push_local_input(jump) {
+                        pushed[index] += 1;
+                    }
+                    while let Some(inputs) = peer.try_advance() {
+                        streams[index].push(inputs);
+                    }
+                }
+                peer.send();
+            }
+            if streams.iter().all(|stream| stream.len() >= TICKS) {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_micros(200));
+        }
+
+        assert_eq!(host.round_seed(), Some(42));
+        assert_eq!(client.round_seed(), Some(42));
+        assert!(streams[0].len() >= TICKS, "host only reached tick {}", streams[0].len());
+        assert_eq!(streams[0][..TICKS], streams[1][..TICKS]);
+        assert!(streams[0].iter().any(|inputs| inputs[0]) && streams[0].iter().any(|inputs| inputs[1]));
+    }
+
+    #[test]
+    fn input_packets_far_beyond_the_window_are_dropped() {
+        let mut host = Lockstep::host(0, 7).unwrap();
+        let peer: SocketAddr = "127.0.0.1:9".parse().unwrap();
+        host.peer = Some(peer);
+
+        let mut packet = vec![PACKET_INPUT];
+        packet.extend_from_slice(&0u32.to_le_bytes());
+        packet.extend_from_slice(&(u32::MAX - 10).to_le_bytes());
+        packet.push(1);
+        packet.extend_from_slice(&1u64.to_le_bytes());
+        packet.extend_from_slice(&0u32.to_le_bytes());
+        host.handle_packet(&packet, peer);
+        assert_eq!(host.remote_inputs.len(), DEFAULT_INPUT_DELAY as usize);
+    }
+}
@@ -1,21 +1,62 @@
+// Bevy 的查询类型天然较长、系统参数天然较多，统一关闭这两个 lint
+#![allow(clippy::type_complexity, clippy::too_many_arguments)]
+
 use bevy::prelude::*;
+use dino_game::difficulty::{Difficulty, DifficultyPresets};
+use dino_game::generator::FairGenerator;
+use dino_game::neuro::Genome;
+use dino_game::powerup::PowerUpKind;
+use dino_game::settings::{ScoreMode, Settings};
+use dino_game::sheet::Clip;
+use dino_game::sim::{
+    CACTUS_CONFIGS, DISTANCE_PER_POINT, GAME_SPEED, GRAVITY, GROUND_Y, JUMP_SPEED, MILESTONE_POINTS,
+    WINDOW_WIDTH,
+};
 use rand::prelude::*;
 
+mod achievements;
+mod ai;
+mod challenge;
+mod courses;
+mod daynight;
+mod editor;
+mod events;
+mod feedback;
+mod ghost;
+mod leaderboards;
+mod menu;
+mod netplay;
+mod parallax;
+mod particles;
+mod powerups;
+mod skin;
+mod sound;
+mod statistics;
+
 // 游戏常量
-const WINDOW_WIDTH: f32 = 800.0;
 const WINDOW_HEIGHT: f32 = 400.0;
-const GROUND_Y: f32 = -150.0;
-const GRAVITY: f32 = -1200.0;
-const JUMP_SPEED: f32 = 500.0;
-const GAME_SPEED: f32 = 300.0;
-const TARGET_FPS: f64 = 60.0; // 目标帧率，适合大多数显示器
+const MAX_PLAYERS: usize = 2;
+const LANE_SPACING: f32 = 170.0; // 双人模式上下两条跑道的间距
+const MILESTONE_FLASH_SECS: f32 = 1.0; // 达到里程碑后分数闪烁的时长
+const MILESTONE_BLINK_SECS: f32 = 0.125; // 闪烁时每次显示或隐藏的时长
+const COIN_POINTS: u32 = 5; // 一枚金币的基础分，乘以连击倍率
+const MAX_COMBO: u32 = 5; // 连击倍率上限
+const COIN_SIZE: f32 = 14.0;
+const COIN_MIN_GAP: f32 = 1.0; // 障碍物间隔至少这么多秒时才在中间放金币，保证吃金币后来得及再起跳
+const COIN_HEIGHTS: [f32; 2] = [45.0, 75.0]; // 金币相对恐龙站立高度的位置，都需要起跳才能吃到
+const LIVES: u32 = 3; // 生命模式下每局的命数
+const INVULNERABLE_SECS: f32 = 1.5; // 丢命后的无敌时间
+const INVULNERABLE_BLINK_SECS: f32 = 0.1; // 无敌期间闪烁的间隔
+const COIN_DEFAULT_COLOR: Color = Color::srgb(1.0, 0.8, 0.1);
 
 // 游戏状态
 #[derive(States, Debug, Clone, PartialEq, Eq, Hash, Default)]
 enum GameState {
     #[default]
+    Menu,
     Playing,
     GameOver,
+    Editor, // 关卡编辑器，世界暂停
 }
 
 // 组件定义
@@ -24,20 +65,87 @@ struct Player {
     velocity_y: f32,
     is_jumping: bool,
     jump_cooldown: f32, // 跳跃冷却时间
+    crashed: bool,      // 双人模式下先撞车的一方停在原地等待对手
+    ducking: bool,      // 在地面上按住下蹲键，目前只影响动画
 }
 
-#[derive(Component)]
-struct AnimationTimer(Timer);
+// 所在跑道，同时也是玩家编号；单人模式只有 0 号跑道
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+struct Lane(usize);
+
+// 恐龙的动画片段，名称与精灵表描述里的 clip 对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DinoClip {
+    Idle,
+    Run,
+    Jump, // 上升
+    Fall, // 下落
+    Land, // 落地时的短暂压扁
+    Duck,
+    Dead,
+}
+
+impl DinoClip {
+    const ALL: [DinoClip; 7] = [
+        DinoClip::Idle,
+        DinoClip::Run,
+        DinoClip::Jump,
+        DinoClip::Fall,
+        DinoClip::Land,
+        DinoClip::Duck,
+        DinoClip::Dead,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            DinoClip::Idle => "idle",
+            DinoClip::Run => "run",
+            DinoClip::Jump => "jump",
+            DinoClip::Fall => "fall",
+            DinoClip::Land => "land",
+            DinoClip::Duck => "duck",
+            DinoClip::Dead => "dead",
+        }
+    }
+}
 
 #[derive(Component)]
 struct DinoAnimation {
-    frames: Vec<Handle<Image>>,
-    current_frame: usize,
+    clip: DinoClip,
+    elapsed: f32, // 当前片段已播放的时间
+}
+
+impl DinoAnimation {
+    fn new(clip: DinoClip) -> Self {
+        Self { clip, elapsed: 0.0 }
+    }
+
+    // 切换片段时从头播放
+    fn play(&mut self, clip: DinoClip) {
+        if self.clip != clip {
+            self.clip = clip;
+            self.elapsed = 0.0;
+        }
+    }
+}
+
+// 剩余的命数，不开启生命模式时只有 1 条命；幽灵没有该组件
+#[derive(Component, Default)]
+struct Lives {
+    remaining: u32,
+    invulnerable: f32, // 剩余的无敌时间
 }
 
 #[derive(Component)]
 struct Obstacle {
-    scored: bool, // 是否已经计分
+    scored: bool,  // 是否已经计分
+    cactus: usize, // CACTUS_CONFIGS 的下标
+}
+
+// 悬在空中的金币，与障碍物一样随地面移动
+#[derive(Component)]
+struct Collectible {
+    missed: bool, // 已经错过，连击已重置
 }
 
 #[derive(Component)]
@@ -46,33 +154,110 @@ struct Ground;
 #[derive(Component)]
 struct Velocity {
     x: f32,
-    #[allow(dead_code)]
-    y: f32,
 }
 
-#[derive(Component)]
+#[derive(Component, Default)]
 struct GameScore {
-    value: u32,
+    value: u32,    // 按当前计分方式显示的分数
+    cleared: u32,  // 跳过的障碍物数量
+    distance: f32, // 跑过的距离（像素）
+    flash: f32,    // 里程碑闪烁的剩余时间
+    bonus: u32,    // 金币得分，两种计分方式都会加上
+    coins: u32,    // 吃到的金币数量
+    combo: u32,    // 连续吃到的金币数量，错过一枚就归零
+}
+
+impl GameScore {
+    // 下一枚金币的倍率
+    fn multiplier(&self) -> u32 {
+        (self.combo + 1).min(MAX_COMBO)
+    }
 }
 
 // 资源定义
 #[derive(Resource)]
 struct GameAssets {
-    dino_frames: Vec<Handle<Image>>,
+    dino_sheet: Handle<Image>,
+    dino_layout: Handle<TextureAtlasLayout>,
+    dino_clips: Vec<Clip>, // 按 DinoClip::ALL 的顺序
     cactus_textures: Vec<Handle<Image>>,
     ground_texture: Handle<Image>,
+    coin_color: Color,
+}
+
+impl GameAssets {
+    fn dino_clip(&self, clip: DinoClip) -> &Clip {
+        &self.dino_clips[clip as usize]
+    }
+
+    // 显示指定片段第一帧的恐龙精灵
+    fn dino_sprite(&self, clip: DinoClip) -> Sprite {
+        Sprite {
+            custom_size: Some(Vec2::new(40.0, 40.0)),
+            ..Sprite::from_atlas_image(
+                self.dino_sheet.clone(),
+                TextureAtlas {
+                    layout: self.dino_layout.clone(),
+                    index: self.dino_clip(clip).frame_at(0.0),
+                },
+            )
+        }
+    }
 }
 
 #[derive(Resource)]
 struct ObstacleTimer(Timer);
 
-// 输入状态资源
+// 公平生成器记录的落地位置，每局重置
+#[derive(Resource, Default)]
+struct ObstacleGenerator(FairGenerator);
+
+// 三个难度预设的难度曲线
+#[derive(Resource)]
+struct Difficulties(DifficultyPresets);
+
+// 当前的世界滚动速度（像素/秒），随难度曲线的分段变化
+#[derive(Resource)]
+struct WorldSpeed(f32);
+
+// 障碍物随机数生成器，每局开始时按种子重置，同一种子得到相同的障碍物序列
+#[derive(Resource)]
+struct GameRng(StdRng);
+
+// 参赛玩家数量（1 或 2）及跑道布局
+#[derive(Resource, Clone, Copy)]
+struct LocalPlayers {
+    count: usize,
+    stacked: bool, // 本地双人上下分道；联机时双方在同一条地面上
+}
+
+impl LocalPlayers {
+    // 跑道地面高度：单人模式就是 GROUND_Y，双人分道模式 0 号跑道在上方
+    fn ground_y(self, lane: Lane) -> f32 {
+        if !self.stacked {
+            return GROUND_Y;
+        }
+        GROUND_Y + (self.count - 1 - lane.0) as f32 * LANE_SPACING
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+struct JumpButton {
+    pressed: bool,
+    just_pressed: bool,
+    duck: bool, // 下蹲键是否按住
+}
+
+// 输入状态资源，每个玩家一份
 #[derive(Resource)]
 struct InputState {
-    space_pressed: bool,
-    space_just_pressed: bool,
-    #[allow(dead_code)]
-    last_jump_time: f32,
+    players: [JumpButton; MAX_PLAYERS],
+}
+
+impl InputState {
+    fn any_just_pressed(&self) -> bool {
+        self.players.iter().any(|button| button.just_pressed)
+    }
 }
 
 // 性能监控资源
@@ -93,9 +278,72 @@ struct FpsText;
 #[derive(Component)]
 struct GameOverText;
 
+// 命令行启动参数
+#[derive(Default)]
+struct LaunchOptions {
+    ai_genome: Option<std::path::PathBuf>, // --ai <文件>：由训练好的网络代替玩家
+    ghost: bool,                           // --ghost：与最佳记录的幽灵赛跑
+    two_player: bool,                      // --two-player：本地双人分道赛跑
+    host: Option<u16>,                     // --host <端口>：作为主机等待联机对手
+    join: Option<String>,                  // --join <地址:端口>：加入联机对战
+    net_delay: Option<u32>,                // --net-delay <tick>：联机输入延迟
+    net_loss: f64,                         // --net-loss <0~1>：模拟丢包率，用于测试
+    theme: Option<String>,                 // --theme <名称>：本次使用的主题，不写入设置
+}
+
+fn parse_launch_options() -> LaunchOptions {
+    let mut options = LaunchOptions::default();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--ai" => options.ai_genome = args.next().map(Into::into),
+            "--ghost" => options.ghost = true,
+            "--two-player" => options.two_player = true,
+            "--host" => options.host = args.next().and_then(|port| port.parse().ok()),
+            "--join" => options.join = args.next(),
+            "--net-delay" => options.net_delay = args.next().and_then(|ticks| ticks.parse().ok()),
+            "--net-loss" => options.net_loss = args.next().and_then(|loss| loss.parse().ok()).unwrap_or(0.0),
+            "--theme" => options.theme = args.next(),
+            _ => eprintln!("忽略未知参数: {}", arg),
+        }
+    }
+    options
+}
+
+// 根据 --host / --join 建立联机会话，失败时退回单机模式
+fn start_net_session(options: &LaunchOptions) -> Option<netplay::NetSession> {
+    let result = match (options.host, &options.join) {
+        (Some(port), _) => dino_game::lockstep::Lockstep::host(port, rand::rng().random()),
+        (None, Some(address)) => dino_game::lockstep::Lockstep::join(address.as_str()),
+        (None, None) => return None,
+    };
+
+    match result {
+        Ok(mut lockstep) => {
+            if let Some(ticks) = options.net_delay {
+                lockstep.set_input_delay(ticks);
+            }
+            lockstep.set_packet_loss(options.net_loss);
+            Some(netplay::NetSession::new(lockstep))
+        }
+        Err(e) => {
+            eprintln!("无法建立联机会话: {}", e);
+            None
+        }
+    }
+}
+
 fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
+    let options = parse_launch_options();
+    let net_session = start_net_session(&options);
+    let players = LocalPlayers {
+        count: if options.two_player || net_session.is_some() { 2 } else { 1 },
+        stacked: options.two_player && net_session.is_none(),
+    };
+    let settings = Settings::load();
+
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "小恐龙跳跃游戏".to_string(),
                 resolution: (WINDOW_WIDTH, WINDOW_HEIGHT).into(),
@@ -114,18 +362,52 @@ fn main() {
                 }
             ),
             ..default()
-        }))
-        
-        .insert_resource(ClearColor(Color::srgb(0.9, 0.9, 0.9))) // 灰白色背景
-        .init_state::<GameState>()
+        }));
+
+    // 日志在 DefaultPlugins 里初始化，之后加载主题才能看到加载失败的提示
+    let theme = skin::load_theme(options.theme.as_deref().unwrap_or(&settings.theme));
+
+    app.add_plugins(events::GameEventsPlugin)
+        .add_plugins(ghost::GhostPlugin {
+            // 幽灵录像只对应单人局
+            enabled: options.ghost && players.count == 1,
+            record: players.count == 1,
+        })
+        .add_plugins(daynight::DayNightPlugin)
+        .add_plugins(parallax::ParallaxPlugin)
+        .add_plugins(particles::ParticlePlugin)
+        .add_plugins(feedback::FeedbackPlugin)
+        .add_plugins(sound::SoundPlugin)
+        .add_plugins(powerups::PowerUpPlugin {
+            enabled: net_session.is_none(),
+        })
+        .add_plugins(skin::SkinPlugin)
+        .insert_resource(skin::ActiveTheme(theme))
+        .insert_resource(skin::UserSettings(settings))
+        .add_plugins(menu::MenuPlugin)
+        .add_plugins(courses::CoursePlugin)
+        .add_plugins(challenge::DailyPlugin)
+        .add_plugins(editor::EditorPlugin)
+        .add_plugins(leaderboards::LeaderboardPlugin)
+        .add_plugins(achievements::AchievementPlugin)
+        .add_plugins(statistics::StatsPlugin)
+        .insert_resource(players)
+        // 联机时双方直接开局，不经过菜单
+        .insert_state(if net_session.is_some() { GameState::Playing } else { GameState::Menu })
+        .insert_resource(GameRng(StdRng::seed_from_u64(0)))
+        // 难度配置文件不存在或损坏时使用内置默认值
+        .insert_resource(Difficulties(DifficultyPresets::load(&skin::assets_root()).unwrap_or_else(|e| {
+            warn!("无法加载难度配置: {}，使用默认配置", e);
+            DifficultyPresets::default()
+        })))
+        .insert_resource(WorldSpeed(GAME_SPEED))
+        .init_resource::<ObstacleGenerator>()
         .insert_resource(ObstacleTimer(Timer::from_seconds(
             2.0,
             TimerMode::Repeating,
         )))
         .insert_resource(InputState {
-            space_pressed: false,
-            space_just_pressed: false,
-            last_jump_time: 0.0,
+            players: [JumpButton::default(); MAX_PLAYERS],
         })
         .insert_resource(PerformanceStats {
             frame_time: 0.0,
@@ -137,27 +419,61 @@ fn main() {
             frame_time_samples: Vec::with_capacity(60),
         })
         .add_systems(Startup, (setup_camera, load_assets))
+        .add_systems(Update, load_assets.run_if(resource_changed::<skin::ActiveTheme>))
+        .add_systems(Update, refresh_theme_sprites.run_if(resource_changed::<GameAssets>))
+        .add_systems(Update, (handle_input, animate_dino, flash_scores, update_world_speed.after(powerups::apply_slow_mo)))
         .add_systems(PostStartup, (spawn_ground, spawn_player))
         .add_systems(
             Update,
             (
                 update_performance_stats,
-                handle_input,
                 player_input,
                 apply_gravity,
                 move_obstacles,
-                spawn_obstacles,
+                spawn_obstacles.run_if(courses::endless),
                 check_collisions,
+                collect_coins,
                 update_score,
                 despawn_offscreen,
                 spawn_ground_tiles,
-                animate_dino,
                 update_fps_display,
             )
                 .run_if(in_state(GameState::Playing)),
         )
-        .add_systems(Update, (handle_input, restart_game, show_game_over_screen).run_if(in_state(GameState::GameOver)))
-        .run();
+        .add_systems(OnEnter(GameState::Playing), reset_lives)
+        .add_systems(Update, blink_invulnerable)
+        .add_systems(Update, restart_game.run_if(in_state(GameState::GameOver).and(not(leaderboards::typing))))
+        .add_systems(Update, show_game_over_screen.run_if(in_state(GameState::GameOver)))
+        .add_systems(OnExit(GameState::GameOver), reset_world)
+        .add_systems(
+            Update,
+            ai::ai_player_input
+                .after(handle_input)
+                .before(player_input)
+                .run_if(in_state(GameState::Playing).and(resource_exists::<ai::AiPlayer>)),
+        );
+
+    // 联机时 AI 代替本机玩家出战
+    let ai_lane = match &net_session {
+        Some(session) => session.local_lane,
+        None => Lane(players.count - 1),
+    };
+    if let Some(session) = net_session {
+        app.insert_resource(session).add_plugins(netplay::NetPlugin);
+    }
+
+    // 加载 AI 基因组，失败时退回键盘操作
+    if let Some(path) = options.ai_genome {
+        match Genome::load(&path) {
+            Ok(genome) => {
+                // 本地双人模式下 AI 接管 2 号玩家，可以人机对战
+                app.insert_resource(ai::AiPlayer { genome, lane: ai_lane });
+            }
+            Err(e) => warn!("无法加载 AI 基因组 {}: {}", path.display(), e),
+        }
+    }
+
+    app.run();
 }
 
 // 初始化系统
@@ -165,29 +481,60 @@ fn setup_camera(mut commands: Commands) {
     commands.spawn(Camera2d);
 }
 
-fn load_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
-    // 加载恐龙动画帧
-    let dino_frames = vec![
-        asset_server.load("sprites/dino1.png"),
-        asset_server.load("sprites/dino2.png"),
-    ];
+// 按当前主题加载贴图，切换主题后重新加载
+fn load_assets(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    theme: Res<skin::ActiveTheme>,
+) {
+    let theme = &theme.0;
 
-    // 加载多种仙人掌图片
-    let cactus_textures = vec![
-        asset_server.load("sprites/cactus1.png"),
-        asset_server.load("sprites/cactus2.png"),
-    ];
+    // 加载恐龙精灵表，按网格切分成帧；描述里缺少的片段停在第 0 帧
+    let sheet = &theme.dino_sheet;
+    let dino_sheet = asset_server.load(&sheet.image);
+    let dino_layout = layouts.add(TextureAtlasLayout::from_grid(
+        UVec2::new(sheet.frame_size.0, sheet.frame_size.1),
+        sheet.columns,
+        sheet.rows,
+        None,
+        None,
+    ));
+    let dino_clips = DinoClip::ALL
+        .iter()
+        .map(|clip| {
+            sheet.clips.get(clip.name()).cloned().unwrap_or(Clip {
+                frames: vec![0],
+                frame_duration: 1.0,
+                looping: false,
+            })
+        })
+        .collect();
 
-    let ground_texture = asset_server.load("sprites/ground.png");
+    // 加载多种仙人掌图片，与 CACTUS_CONFIGS 一一对应
+    let cactus_textures = theme.obstacles.iter().map(|path| asset_server.load(path)).collect();
+
+    let ground_texture = asset_server.load(&theme.ground);
+    let coin_color = theme.color("coin").map_or(COIN_DEFAULT_COLOR, skin::to_color);
 
     commands.insert_resource(GameAssets {
-        dino_frames,
+        dino_sheet,
+        dino_layout,
+        dino_clips,
         cactus_textures,
         ground_texture,
+        coin_color,
     });
 }
 
-fn spawn_ground(mut commands: Commands, assets: Res<GameAssets>) {
+fn spawn_ground(mut commands: Commands, assets: Res<GameAssets>, players: Res<LocalPlayers>, speed: Res<WorldSpeed>) {
+    for lane in (0..players.count).map(Lane) {
+        spawn_lane_ground(&mut commands, &assets, lane, players.ground_y(lane), speed.0);
+    }
+}
+
+// 生成一条跑道的初始地面
+fn spawn_lane_ground(commands: &mut Commands, assets: &GameAssets, lane: Lane, ground_y: f32, speed: f32) {
     // 计算需要覆盖的范围：从屏幕左边延伸到右边，再多加一些缓冲
     let start_x = -WINDOW_WIDTH / 2.0 - 200.0; // 屏幕左边缘再往左200px
     let end_x = WINDOW_WIDTH / 2.0 + 400.0; // 屏幕右边缘再往右400px
@@ -199,50 +546,93 @@ fn spawn_ground(mut commands: Commands, assets: Res<GameAssets>) {
         commands.spawn((
             Sprite {
                 image: assets.ground_texture.clone(),
-                color: Color::srgb(0.55, 0.27, 0.07), // 棕色色调
                 custom_size: Some(Vec2::new(tile_width, 20.0)),
                 ..default()
             },
-            Transform::from_xyz(start_x + i as f32 * tile_width, GROUND_Y, 0.0),
+            Transform::from_xyz(start_x + i as f32 * tile_width, ground_y, 0.0),
             Ground,
-            Velocity {
-                x: -GAME_SPEED,
-                y: 0.0,
-            },
+            lane,
+            Velocity { x: -speed },
         ));
     }
 }
 
-fn spawn_player(mut commands: Commands, assets: Res<GameAssets>) {
+fn spawn_player(mut commands: Commands, assets: Res<GameAssets>, players: Res<LocalPlayers>) {
+    for lane in (0..players.count).map(Lane) {
+        spawn_dino(&mut commands, &assets, lane, players.ground_y(lane));
+        spawn_score_text(&mut commands, *players, lane);
+    }
+
+    spawn_fps_text(&mut commands);
+}
+
+fn spawn_dino(commands: &mut Commands, assets: &GameAssets, lane: Lane, ground_y: f32) {
     commands.spawn((
-        Sprite {
-            image: assets.dino_frames[0].clone(),
-            custom_size: Some(Vec2::new(40.0, 40.0)),
-            ..default()
-        },
-        Transform::from_xyz(-300.0, GROUND_Y + 30.0, 1.0),
+        assets.dino_sprite(DinoClip::Run),
+        Transform::from_xyz(-300.0, ground_y + 30.0, 1.0),
         Player {
             velocity_y: 0.0,
             is_jumping: false,
             jump_cooldown: 0.0,
+            crashed: false,
+            ducking: false,
         },
-        DinoAnimation {
-            frames: assets.dino_frames.clone(),
-            current_frame: 0,
-        },
-        AnimationTimer(Timer::from_seconds(0.2, TimerMode::Repeating)),
+        lane,
+        DinoAnimation::new(DinoClip::Run),
+        powerups::ActivePowerUps::default(),
+        Lives::default(),
     ));
+}
 
-    // 生成分数显示
-    commands.spawn((
-        GameScore { value: 0 },
-        Text2d::new("Score: 0"),
-        Transform::from_xyz(-350.0, 150.0, 1.0),
-    ));
+// 生成分数显示，双人模式下每条跑道上方各一个
+fn spawn_score_text(commands: &mut Commands, players: LocalPlayers, lane: Lane) {
+    let y = if players.stacked {
+        players.ground_y(lane) + 155.0
+    } else {
+        150.0 - lane.0 as f32 * 25.0
+    };
+    commands
+        .spawn((
+            GameScore::default(),
+            lane,
+            daynight::HudText,
+            Text2d::new(score_label(players, lane, &GameScore::default(), None)),
+            // 左对齐，金币和连击信息变长时不会超出屏幕
+            bevy::sprite::Anchor::CenterLeft,
+            Transform::from_xyz(-390.0, y, 1.0),
+        ))
+        .with_child((
+            powerups::PowerUpText,
+            lane,
+            daynight::HudText,
+            Text2d::default(),
+            bevy::sprite::Anchor::CenterLeft,
+            Transform::from_xyz(0.0, -24.0, 0.0),
+            TextFont {
+                font_size: 18.0,
+                ..default()
+            },
+        ));
+}
 
-    // 生成FPS显示
+// lives 只在生命模式下显示
+fn score_label(players: LocalPlayers, lane: Lane, score: &GameScore, lives: Option<u32>) -> String {
+    let label = if players.count == 1 {
+        format!("Score: {}  Coins: {} x{}", score.value, score.coins, score.multiplier())
+    } else {
+        format!("P{}: {}  x{}", lane.0 + 1, score.value, score.multiplier())
+    };
+    match lives {
+        Some(lives) => format!("{}  Lives: {}", label, lives),
+        None => label,
+    }
+}
+
+// 生成FPS显示
+fn spawn_fps_text(commands: &mut Commands) {
     commands.spawn((
         FpsText,
+        daynight::HudText,
         Text2d::new("FPS: 60"),
         Transform::from_xyz(300.0, 150.0, 1.0),
         TextFont {
@@ -255,25 +645,55 @@ fn spawn_player(mut commands: Commands, assets: Res<GameAssets>) {
 // 优化的输入处理系统
 fn handle_input(
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<(Entity, &Gamepad)>,
+    players: Res<LocalPlayers>,
     _time: Res<Time>,
     mut input_state: ResMut<InputState>,
 ) {
-    
-    // 检测空格键状态
-    let space_pressed_now = keyboard_input.pressed(KeyCode::Space) || keyboard_input.pressed(KeyCode::ArrowUp);
-    
-    // 更新输入状态
-    input_state.space_just_pressed = space_pressed_now && !input_state.space_pressed;
-    input_state.space_pressed = space_pressed_now;
+    // 按连接顺序给手柄分配玩家
+    let mut gamepads: Vec<(Entity, &Gamepad)> = gamepads.iter().collect();
+    gamepads.sort_by_key(|(entity, _)| *entity);
+
+    for lane in 0..players.count {
+        // 单人：空格/上箭头/任意手柄，下箭头/S 下蹲；
+        // 双人：1P 空格/W 跳、S 蹲，2P 上箭头/回车跳、下箭头蹲，手柄按顺序分配
+        let lane_gamepads: Vec<&Gamepad> = if players.count == 1 {
+            gamepads.iter().map(|(_, gamepad)| *gamepad).collect()
+        } else {
+            gamepads.get(lane).map(|(_, gamepad)| *gamepad).into_iter().collect()
+        };
+        let (jump_keys, duck_keys): (&[KeyCode], &[KeyCode]) = if players.count == 1 {
+            (&[KeyCode::Space, KeyCode::ArrowUp], &[KeyCode::ArrowDown, KeyCode::KeyS])
+        } else if lane == 0 {
+            (&[KeyCode::Space, KeyCode::KeyW], &[KeyCode::KeyS])
+        } else {
+            (&[KeyCode::ArrowUp, KeyCode::Enter], &[KeyCode::ArrowDown])
+        };
+        let pressed_now = keyboard_input.any_pressed(jump_keys.iter().copied())
+            || lane_gamepads.iter().any(|gamepad| gamepad.pressed(GamepadButton::South));
+
+        // 更新输入状态
+        let button = &mut input_state.players[lane];
+        button.just_pressed = pressed_now && !button.pressed;
+        button.pressed = pressed_now;
+        button.duck = keyboard_input.any_pressed(duck_keys.iter().copied())
+            || lane_gamepads.iter().any(|gamepad| gamepad.pressed(GamepadButton::DPadDown));
+    }
 }
 
 // 游戏逻辑系统
 fn player_input(
     time: Res<Time>,
     input_state: Res<InputState>,
-    mut player_query: Query<(&mut Player, &Transform)>,
+    players: Res<LocalPlayers>,
+    mut current_run: ResMut<ghost::CurrentRun>,
+    mut jumped: EventWriter<events::Jumped>,
+    mut player_query: Query<(&mut Player, &Transform, &Lane, Option<&mut powerups::ActivePowerUps>), Without<ghost::Ghost>>,
 ) {
-    if let Ok((mut player, transform)) = player_query.single_mut() {
+    for (mut player, transform, &lane, mut active) in player_query.iter_mut() {
+        if player.crashed {
+            continue;
+        }
         let _current_time = time.elapsed_secs();
         
         // 更新跳跃冷却时间
@@ -282,22 +702,50 @@ fn player_input(
         }
         
         // 检查是否在地面上（用于判断是否可以跳跃）
-        let on_ground = transform.translation.y <= GROUND_Y + 30.0;
+        let on_ground = transform.translation.y <= players.ground_y(lane) + 30.0;
+
+        // 二段跳道具生效时，每次腾空可以在空中再跳一次
+        let can_air_jump = active.as_ref().is_some_and(|active| {
+            active.is_active(PowerUpKind::DoubleJump) && !active.air_jumped
+        });
+        if let Some(active) = active.as_mut().filter(|_| on_ground) {
+            active.air_jumped = false;
+        }
 
         // 使用优化的输入检测
-        if input_state.space_just_pressed
-            && on_ground 
+        if input_state.players[lane.0].just_pressed
+            && (on_ground || can_air_jump)
             && player.jump_cooldown <= 0.0
         {
+            if let Some(active) = active.as_mut().filter(|_| !on_ground) {
+                active.air_jumped = true;
+            }
             player.velocity_y = JUMP_SPEED;
             player.is_jumping = true;
             player.jump_cooldown = 0.1; // 设置跳跃冷却时间
+            if lane.0 == 0 {
+                current_run.0.record_jump(); // 记录起跳时间，供幽灵回放
+            }
+            jumped.write(events::Jumped {
+                lane,
+                air_jump: !on_ground,
+                position: transform.translation.truncate(),
+            });
         }
+        player.ducking = on_ground && !player.is_jumping && input_state.players[lane.0].duck;
     }
 }
 
-fn apply_gravity(time: Res<Time>, mut player_query: Query<(&mut Player, &mut Transform)>) {
-    if let Ok((mut player, mut transform)) = player_query.single_mut() {
+// 玩家和幽灵共用同一套重力
+fn apply_gravity(
+    time: Res<Time>,
+    players: Res<LocalPlayers>,
+    mut landed: EventWriter<events::Landed>,
+    mut player_query: Query<(&mut Player, &mut Transform, &Lane, Has<ghost::Ghost>)>,
+) {
+    for (mut player, mut transform, &lane, is_ghost) in player_query.iter_mut() {
+        let rest_y = players.ground_y(lane) + 30.0;
+
         // 应用重力
         player.velocity_y += GRAVITY * time.delta_secs();
 
@@ -305,8 +753,15 @@ fn apply_gravity(time: Res<Time>, mut player_query: Query<(&mut Player, &mut Tra
         transform.translation.y += player.velocity_y * time.delta_secs();
 
         // 检查是否着地
-        if transform.translation.y <= GROUND_Y + 30.0 {
-            transform.translation.y = GROUND_Y + 30.0;
+        if transform.translation.y <= rest_y {
+            // 幽灵落地不发出事件
+            if player.is_jumping && !is_ghost {
+                landed.write(events::Landed {
+                    lane,
+                    position: Vec2::new(transform.translation.x, rest_y - 20.0),
+                });
+            }
+            transform.translation.y = rest_y;
             player.velocity_y = 0.0;
             player.is_jumping = false;
         }
@@ -323,63 +778,164 @@ fn spawn_obstacles(
     mut commands: Commands,
     time: Res<Time>,
     mut timer: ResMut<ObstacleTimer>,
+    mut generator: ResMut<ObstacleGenerator>,
+    mut rng: ResMut<GameRng>,
+    players: Res<LocalPlayers>,
     assets: Res<GameAssets>,
+    speed: Res<WorldSpeed>,
+    difficulties: Res<Difficulties>,
+    settings: Res<skin::UserSettings>,
+    net_session: Option<Res<netplay::NetSession>>,
+    daily: Res<challenge::DailyChallenge>,
+    powerup_table: Res<powerups::PowerUps>,
+    slow_mo: Res<powerups::SlowMo>,
+    score_query: Query<&GameScore>,
 ) {
-    timer.0.tick(time.delta());
+    // 慢动作时出障碍物的节奏同样放慢，障碍物之间的距离保持不变
+    timer.0.tick(time.delta().mul_f32(slow_mo.0));
 
     if timer.0.just_finished() {
-        // 使用thread_local的RNG，避免重复初始化
-        thread_local! {
-            static RNG: std::cell::RefCell<rand::rngs::ThreadRng> = std::cell::RefCell::new(rand::rng());
+        let rng = &mut rng.0;
+        // 按领先玩家跳过的障碍物数量选取难度分段，两条跑道共用同一段
+        let score = score_query.iter().map(|score| score.cleared).max().unwrap_or(0);
+        let difficulty = current_difficulty(&settings.0, net_session.is_some() || daily.active);
+        // 生成器保证每一簇都能一次跳过、每段间隔都来得及落地再跳；
+        // 这一簇经过恐龙之前随时可能吃到慢动作道具，按最慢的世界速度检查
+        let slow_factor = powerup_table.0.slowest_speed_factor();
+        let pattern = generator.0.next(rng, difficulties.0.curve(difficulty), score, slow_factor);
+
+        let mut cluster_width = 0.0;
+        if let Some((cactus_index, count)) = pattern.cluster {
+            // 尺寸配置与训练用的模拟共用，一簇里的障碍物种类相同、紧挨着排列
+            let (width, _) = CACTUS_CONFIGS[cactus_index];
+            cluster_width = width * count as f32;
+
+            // 每条跑道放相同的障碍物，双人模式下两人面对同样的障碍序列
+            for lane in (0..players.count).map(Lane) {
+                for i in 0..count {
+                    let x = 500.0 + i as f32 * width;
+                    spawn_cactus(&mut commands, &assets, cactus_index, x, players.ground_y(lane), lane, speed.0);
+                }
+            }
         }
-        
-        RNG.with(|rng| {
-            let mut rng = rng.borrow_mut();
-            
-            if rng.random_bool(0.85) {
-                // 85% 概率生成障碍物
-                // 预计算的障碍物配置，避免运行时计算
-                static CACTUS_CONFIGS: [(f32, f32); 2] = [
-                    (25.0, 45.0), // cactus1 - 较小
-                    (35.0, 55.0), // cactus2 - 较大
-                ];
-                
-                let cactus_index = rng.random_range(0..assets.cactus_textures.len());
-                let (width, height) = CACTUS_CONFIGS[cactus_index];
 
+        let next_interval = pattern.interval;
+
+        // 间隔足够长时在这一簇障碍物和下一个障碍物正中间的空中放一枚金币；
+        // 位置只由间隔决定，不额外消耗随机数，同一种子的障碍物序列不受影响
+        if next_interval >= COIN_MIN_GAP {
+            let height = COIN_HEIGHTS[(next_interval * 100.0) as usize % COIN_HEIGHTS.len()];
+            let x = 500.0 + (cluster_width + speed.0 * next_interval) * 0.5;
+
+            // 有一定概率改放道具；随机数取自障碍物随机数副本的种子，同样不影响障碍物序列
+            let mut pickup_rng = StdRng::seed_from_u64(rng.clone().random());
+            let powerup = powerup_table.0.pick(pickup_rng.random(), pickup_rng.random());
+
+            for lane in (0..players.count).map(Lane) {
+                let y = players.ground_y(lane) + 30.0 + height;
+                if let Some(kind) = powerup {
+                    powerups::spawn_pickup(&mut commands, kind, Vec3::new(x, y, 1.0), lane, speed.0);
+                    continue;
+                }
                 commands.spawn((
-                    Sprite {
-                        image: assets.cactus_textures[cactus_index].clone(),
-                        custom_size: Some(Vec2::new(width, height)),
-                        ..default()
-                    },
-                    Transform::from_xyz(500.0, GROUND_Y + height * 0.5, 1.0),
-                    Obstacle { scored: false },
-                    Velocity {
-                        x: -GAME_SPEED,
-                        y: 0.0,
-                    },
+                    Sprite::from_color(assets.coin_color, Vec2::splat(COIN_SIZE)),
+                    Transform::from_xyz(x, y, 1.0)
+                        .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+                    Collectible { missed: false },
+                    lane,
+                    Velocity { x: -speed.0 },
                 ));
             }
+        }
+        timer.0.set_duration(std::time::Duration::from_secs_f32(next_interval));
+        timer.0.reset();
+    }
+}
 
-            // 设置下一个障碍物的随机间隔时间
-            let next_interval = rng.random_range(0.5..1.8);
-            timer.0.set_duration(std::time::Duration::from_secs_f32(next_interval));
-            timer.0.reset();
-        });
+// 在跑道上生成一个随地面移动的仙人掌，无尽模式和关卡模式共用
+fn spawn_cactus(commands: &mut Commands, assets: &GameAssets, cactus_index: usize, x: f32, ground_y: f32, lane: Lane, speed: f32) {
+    let (width, height) = CACTUS_CONFIGS[cactus_index];
+    commands.spawn((
+        Sprite {
+            image: assets.cactus_textures[cactus_index].clone(),
+            custom_size: Some(Vec2::new(width, height)),
+            ..default()
+        },
+        Transform::from_xyz(x, ground_y + height * 0.5, 1.0),
+        Obstacle { scored: false, cactus: cactus_index },
+        lane,
+        Velocity { x: -speed },
+    ));
+}
+
+// 当前使用的难度；联机和每日挑战时所有人必须一致，固定为默认难度
+fn current_difficulty(settings: &Settings, fixed: bool) -> Difficulty {
+    if fixed {
+        Difficulty::default()
+    } else {
+        settings.difficulty
+    }
+}
+
+// 计分方式同样在联机和每日挑战时固定，每日最高分才能互相比较
+fn current_score_mode(settings: &Settings, fixed: bool) -> ScoreMode {
+    if fixed {
+        ScoreMode::default()
+    } else {
+        settings.score_mode
+    }
+}
+
+// 难度分段的速度变化或慢动作开始、结束时，让场上所有随地面移动的物体一起换速度；
+// 关卡模式使用关卡固定的速度
+fn update_world_speed(
+    difficulties: Res<Difficulties>,
+    settings: Res<skin::UserSettings>,
+    net_session: Option<Res<netplay::NetSession>>,
+    daily: Res<challenge::DailyChallenge>,
+    course_mode: Res<courses::CourseMode>,
+    slow_mo: Res<powerups::SlowMo>,
+    mut speed: ResMut<WorldSpeed>,
+    score_query: Query<&GameScore>,
+    mut velocity_query: Query<&mut Velocity>,
+) {
+    let score = score_query.iter().map(|score| score.cleared).max().unwrap_or(0);
+    let difficulty = current_difficulty(&settings.0, net_session.is_some() || daily.active);
+    let target = match course_mode.course() {
+        Some(course) => course.speed,
+        None => difficulties.0.curve(difficulty).band(score).speed,
+    } * slow_mo.0;
+    if speed.0 != target {
+        speed.0 = target;
+        for mut velocity in velocity_query.iter_mut() {
+            velocity.x = -target;
+        }
     }
 }
 
 fn check_collisions(
-    mut next_state: ResMut<NextState<GameState>>,
-    player_query: Query<&Transform, (With<Player>, Without<Obstacle>)>,
-    obstacle_query: Query<&Transform, (With<Obstacle>, Without<Player>)>,
+    mut commands: Commands,
+    players: Res<LocalPlayers>,
+    mut smashed: EventWriter<events::ObstacleSmashed>,
+    mut crashes: EventWriter<events::Crashed>,
+    mut player_query: Query<
+        (&mut Player, &mut Sprite, &Transform, &Lane, &mut Lives, Option<&mut powerups::ActivePowerUps>),
+        (Without<Obstacle>, Without<ghost::Ghost>),
+    >,
+    obstacle_query: Query<(Entity, &Obstacle, &Transform, &Lane), Without<Player>>,
 ) {
-    if let Ok(player_transform) = player_query.single() {
+    for (mut player, mut sprite, player_transform, lane, mut lives, mut active) in player_query.iter_mut() {
+        // 丢命后的无敌时间内不做碰撞检测
+        if player.crashed || lives.invulnerable > 0.0 {
+            continue;
+        }
         let player_pos = player_transform.translation;
         
         // 优化：只检查玩家附近的障碍物
-        for obstacle_transform in obstacle_query.iter() {
+        for (obstacle_entity, obstacle, obstacle_transform, obstacle_lane) in obstacle_query.iter() {
+            if obstacle_lane != lane {
+                continue;
+            }
             let obstacle_pos = obstacle_transform.translation;
             
             // 早期退出：如果障碍物太远，跳过
@@ -396,34 +952,186 @@ fn check_collisions(
             // 更精确的矩形碰撞检测
             let collision_threshold = 25.0;
             if dx < collision_threshold && dy < collision_threshold {
-                next_state.set(GameState::GameOver);
-                return; // 早期退出
+                // 护盾挡下这一次撞击，障碍物被撞碎
+                if let Some(active) = active.as_mut().filter(|active| active.is_active(PowerUpKind::Shield)) {
+                    active.consume(PowerUpKind::Shield);
+                    commands.entity(obstacle_entity).despawn();
+                    smashed.write(events::ObstacleSmashed { position: obstacle_pos.truncate() });
+                    break;
+                }
+
+                // 还有命时只扣一条命并短暂无敌，穿过这个障碍物继续跑
+                lives.remaining = lives.remaining.saturating_sub(1);
+                crashes.write(events::Crashed {
+                    lane: *lane,
+                    cactus: obstacle.cactus,
+                    lives_left: lives.remaining,
+                    position: ((player_pos + obstacle_pos) * 0.5).truncate(),
+                });
+                if lives.remaining > 0 {
+                    lives.invulnerable = INVULNERABLE_SECS;
+                    break;
+                }
+
+                player.crashed = true;
+                if players.count > 1 {
+                    sprite.color = Color::srgba(1.0, 1.0, 1.0, 0.4); // 先撞车的一方变淡
+                }
+                break; // 早期退出
+            }
+        }
+    }
+}
+
+// 碰到金币就吃掉并累积连击，金币飞过玩家身后还没吃到则连击归零
+fn collect_coins(
+    mut commands: Commands,
+    mut coins: EventWriter<events::CoinCollected>,
+    mut score_query: Query<(&mut GameScore, &Lane)>,
+    mut coin_query: Query<(Entity, &mut Collectible, &Transform, &Lane), Without<Player>>,
+    player_query: Query<(&Player, &Transform, &Lane), (Without<Collectible>, Without<ghost::Ghost>)>,
+) {
+    for (player, player_transform, lane) in player_query.iter() {
+        if player.crashed {
+            continue;
+        }
+        let Some((mut score, _)) = score_query.iter_mut().find(|(_, score_lane)| *score_lane == lane) else {
+            continue;
+        };
+        let player_pos = player_transform.translation;
+
+        for (entity, mut coin, coin_transform, coin_lane) in coin_query.iter_mut() {
+            if coin_lane != lane || coin.missed {
+                continue;
+            }
+            let coin_pos = coin_transform.translation;
+            if (player_pos.x - coin_pos.x).abs() < 25.0 && (player_pos.y - coin_pos.y).abs() < 25.0 {
+                score.bonus += COIN_POINTS * score.multiplier();
+                score.coins += 1;
+                score.combo += 1;
+                coins.write(events::CoinCollected);
+                commands.entity(entity).despawn();
+            } else if coin_pos.x < player_pos.x - 25.0 {
+                coin.missed = true;
+                score.combo = 0;
             }
         }
     }
 }
 
 fn update_score(
-    mut score_query: Query<(&mut GameScore, &mut Text2d)>,
-    mut obstacle_query: Query<(&mut Obstacle, &Transform), Without<Player>>,
-    player_query: Query<&Transform, (With<Player>, Without<Obstacle>)>,
+    time: Res<Time>,
+    players: Res<LocalPlayers>,
+    settings: Res<skin::UserSettings>,
+    speed: Res<WorldSpeed>,
+    net_session: Option<Res<netplay::NetSession>>,
+    daily: Res<challenge::DailyChallenge>,
+    mut cleared: EventWriter<events::ObstacleCleared>,
+    mut milestones: EventWriter<events::MilestoneReached>,
+    mut score_query: Query<(&mut GameScore, &mut Text2d, &Lane)>,
+    mut obstacle_query: Query<(&mut Obstacle, &Transform, &Lane), Without<Player>>,
+    player_query: Query<(&Player, &Transform, &Lane, &Lives), (Without<Obstacle>, Without<ghost::Ghost>)>,
 ) {
-    if let Ok(player_transform) = player_query.single() {
-        if let Ok((mut score, mut text)) = score_query.single_mut() {
-            // 检测是否有障碍物被跳过
-            for (mut obstacle, obstacle_transform) in obstacle_query.iter_mut() {
-                if !obstacle.scored
+    let fixed = net_session.is_some() || daily.active;
+    let show_lives = lives_per_run(&settings.0, fixed) > 1;
+    let score_mode = current_score_mode(&settings.0, fixed);
+    for (player, player_transform, lane, lives) in player_query.iter() {
+        let Some((mut score, mut text, _)) = score_query.iter_mut().find(|(_, _, score_lane)| *score_lane == lane) else {
+            continue;
+        };
+        if !player.crashed {
+            // 检测本跑道是否有障碍物被跳过
+            for (mut obstacle, obstacle_transform, obstacle_lane) in obstacle_query.iter_mut() {
+                if obstacle_lane == lane
+                    && !obstacle.scored
                     && obstacle_transform.translation.x < player_transform.translation.x
                 {
                     obstacle.scored = true;
-                    score.value += 1; // 跳过一个障碍物得1分
-                    text.0 = format!("Score: {}", score.value);
+                    score.cleared += 1;
+                    cleared.write(events::ObstacleCleared { lane: *lane, cactus: obstacle.cactus });
                 }
             }
+            score.distance += speed.0 * time.delta_secs();
+
+            let value = score.bonus
+                + match score_mode {
+                    ScoreMode::Obstacles => score.cleared, // 跳过一个障碍物得1分
+                    ScoreMode::Distance => (score.distance / DISTANCE_PER_POINT) as u32,
+                };
+            if value / MILESTONE_POINTS > score.value / MILESTONE_POINTS {
+                milestones.write(events::MilestoneReached { lane: *lane });
+            }
+            score.value = value;
+        }
+
+        // 连击和命数变化时分数不一定变化，每帧比较一次文本
+        let label = score_label(*players, *lane, &score, show_lives.then_some(lives.remaining));
+        if text.0 != label {
+            text.0 = label;
         }
     }
 }
 
+// 每局的命数；联机和每日挑战时所有人必须一致，固定为 1 条命
+fn lives_per_run(settings: &Settings, fixed: bool) -> u32 {
+    if settings.lives_mode && !fixed {
+        LIVES
+    } else {
+        1
+    }
+}
+
+// 开局时按设置补满命数
+fn reset_lives(
+    settings: Res<skin::UserSettings>,
+    net_session: Option<Res<netplay::NetSession>>,
+    daily: Res<challenge::DailyChallenge>,
+    mut query: Query<&mut Lives>,
+) {
+    let remaining = lives_per_run(&settings.0, net_session.is_some() || daily.active);
+    for mut lives in query.iter_mut() {
+        *lives = Lives {
+            remaining,
+            invulnerable: 0.0,
+        };
+    }
+}
+
+// 无敌期间恐龙一闪一闪，结束后恢复显示
+fn blink_invulnerable(time: Res<Time>, mut query: Query<(&mut Lives, &mut Visibility)>) {
+    for (mut lives, mut visibility) in query.iter_mut() {
+        if lives.invulnerable <= 0.0 {
+            continue;
+        }
+        lives.invulnerable = (lives.invulnerable - time.delta_secs()).max(0.0);
+        let hidden = lives.invulnerable > 0.0 && (lives.invulnerable / INVULNERABLE_BLINK_SECS) as u32 % 2 == 1;
+        visibility.set_if_neq(if hidden { Visibility::Hidden } else { Visibility::Inherited });
+    }
+}
+
+// 达到里程碑时分数闪烁几下，结束后恢复显示
+fn flash_scores(
+    time: Res<Time>,
+    mut milestones: EventReader<events::MilestoneReached>,
+    mut score_query: Query<(&mut GameScore, &mut Visibility, &Lane)>,
+) {
+    for milestone in milestones.read() {
+        for (mut score, _, lane) in score_query.iter_mut() {
+            if *lane == milestone.lane {
+                score.flash = MILESTONE_FLASH_SECS;
+            }
+        }
+    }
+    for (mut score, mut visibility, _) in score_query.iter_mut() {
+        if score.flash <= 0.0 {
+            continue;
+        }
+        score.flash = (score.flash - time.delta_secs()).max(0.0);
+        let hidden = score.flash > 0.0 && (score.flash / MILESTONE_BLINK_SECS) as u32 % 2 == 1;
+        visibility.set_if_neq(if hidden { Visibility::Hidden } else { Visibility::Inherited });
+    }
+}
+
 fn despawn_offscreen(
     mut commands: Commands,
     query: Query<(Entity, &Transform), (With<Velocity>, Without<Player>)>,
@@ -446,33 +1154,34 @@ fn despawn_offscreen(
 fn spawn_ground_tiles(
     mut commands: Commands,
     assets: Res<GameAssets>,
-    ground_query: Query<&Transform, With<Ground>>,
+    players: Res<LocalPlayers>,
+    speed: Res<WorldSpeed>,
+    ground_query: Query<(&Transform, &Lane), With<Ground>>,
 ) {
-    // 找到最右边的地面块
-    let mut rightmost_x = -400.0;
-    for transform in ground_query.iter() {
-        if transform.translation.x > rightmost_x {
-            rightmost_x = transform.translation.x;
+    for lane in (0..players.count).map(Lane) {
+        // 找到这条跑道最右边的地面块
+        let mut rightmost_x = -400.0;
+        for (transform, ground_lane) in ground_query.iter() {
+            if *ground_lane == lane && transform.translation.x > rightmost_x {
+                rightmost_x = transform.translation.x;
+            }
         }
-    }
 
-    // 如果最右边的地面块位置小于窗口右边缘，就生成新的地面块
-    if rightmost_x < WINDOW_WIDTH / 2.0 + 100.0 {
-        for i in 0..3 {
-            commands.spawn((
-                Sprite {
-                    image: assets.ground_texture.clone(),
-                    color: Color::srgb(0.55, 0.27, 0.07),
-                    custom_size: Some(Vec2::new(100.0, 20.0)),
-                    ..default()
-                },
-                Transform::from_xyz(rightmost_x + 100.0 + (i as f32 * 100.0), GROUND_Y, 0.0),
-                Ground,
-                Velocity {
-                    x: -GAME_SPEED,
-                    y: 0.0,
-                },
-            ));
+        // 如果最右边的地面块位置小于窗口右边缘，就生成新的地面块
+        if rightmost_x < WINDOW_WIDTH / 2.0 + 100.0 {
+            for i in 0..3 {
+                commands.spawn((
+                    Sprite {
+                        image: assets.ground_texture.clone(),
+                        custom_size: Some(Vec2::new(100.0, 20.0)),
+                        ..default()
+                    },
+                    Transform::from_xyz(rightmost_x + 100.0 + (i as f32 * 100.0), players.ground_y(lane), 0.0),
+                    Ground,
+                    lane,
+                    Velocity { x: -speed.0 },
+                ));
+            }
         }
     }
 }
@@ -480,115 +1189,98 @@ fn spawn_ground_tiles(
 fn restart_game(
     mut input_state: ResMut<InputState>,
     mut next_state: ResMut<NextState<GameState>>,
+) {
+    if input_state.any_just_pressed() {
+        // 重置输入状态，避免立即再次重启
+        input_state.players = [JumpButton::default(); MAX_PLAYERS];
+        next_state.set(GameState::Playing);
+    }
+}
+
+// 离开游戏结束画面时（重新开局或回到菜单）清理上一局并重新生成场景
+fn reset_world(
     mut commands: Commands,
     mut obstacle_timer: ResMut<ObstacleTimer>,
-    entities: Query<Entity, Or<(With<Obstacle>, With<GameScore>, With<Ground>, With<Player>, With<FpsText>, With<GameOverText>)>>,
+    mut generator: ResMut<ObstacleGenerator>,
+    players: Res<LocalPlayers>,
+    speed: Res<WorldSpeed>,
+    entities: Query<Entity, Or<(With<Obstacle>, With<Collectible>, With<powerups::PowerUpPickup>, With<GameScore>, With<Ground>, With<Player>, With<FpsText>, With<GameOverText>, With<ghost::GhostDeltaText>)>>,
     assets: Res<GameAssets>,
 ) {
-    if input_state.space_just_pressed {
-        // 清除所有游戏实体
-        for entity in entities.iter() {
-            commands.entity(entity).despawn();
-        }
+    // 清除所有游戏实体
+    for entity in entities.iter() {
+        commands.entity(entity).despawn();
+    }
 
-        // 重新生成地面 - 使用与初始生成相同的逻辑
-        let start_x = -WINDOW_WIDTH / 2.0 - 200.0; // 屏幕左边缘再往左200px
-        let end_x = WINDOW_WIDTH / 2.0 + 400.0; // 屏幕右边缘再往右400px
-        let tile_width = 100.0;
-        let tile_count = ((end_x - start_x) / tile_width).ceil() as i32;
-
-        for i in 0..tile_count {
-            commands.spawn((
-                Sprite {
-                    image: assets.ground_texture.clone(),
-                    color: Color::srgb(0.55, 0.27, 0.07),
-                    custom_size: Some(Vec2::new(tile_width, 20.0)),
-                    ..default()
-                },
-                Transform::from_xyz(start_x + i as f32 * tile_width, GROUND_Y, 0.0),
-                Ground,
-                Velocity {
-                    x: -GAME_SPEED,
-                    y: 0.0,
-                },
-            ));
-        }
+    // 重新生成地面、恐龙和分数显示 - 使用与初始生成相同的逻辑
+    for lane in (0..players.count).map(Lane) {
+        let ground_y = players.ground_y(lane);
+        spawn_lane_ground(&mut commands, &assets, lane, ground_y, speed.0);
+        spawn_dino(&mut commands, &assets, lane, ground_y);
+        spawn_score_text(&mut commands, *players, lane);
+    }
 
-        // 重新生成恐龙
-        commands.spawn((
-            Sprite {
-                image: assets.dino_frames[0].clone(),
-                custom_size: Some(Vec2::new(40.0, 40.0)),
-                ..default()
-            },
-            Transform::from_xyz(-300.0, GROUND_Y + 30.0, 1.0),
-            Player {
-                velocity_y: 0.0,
-                is_jumping: false,
-                jump_cooldown: 0.0,
-            },
-            DinoAnimation {
-                frames: assets.dino_frames.clone(),
-                current_frame: 0,
-            },
-            AnimationTimer(Timer::from_seconds(0.2, TimerMode::Repeating)),
-        ));
+    // 重新生成FPS显示
+    spawn_fps_text(&mut commands);
 
-        // 重新生成分数显示
-        commands.spawn((
-            GameScore { value: 0 },
-            Text2d::new("Score: 0"),
-            Transform::from_xyz(-350.0, 150.0, 1.0),
-        ));
+    // 重置障碍物计时器和公平生成器
+    obstacle_timer.0.set_duration(std::time::Duration::from_secs_f32(2.0));
+    obstacle_timer.0.reset();
+    *generator = ObstacleGenerator::default();
+}
 
-        // 重新生成FPS显示
-        commands.spawn((
-            FpsText,
-            Text2d::new("FPS: 60"),
-            Transform::from_xyz(300.0, 150.0, 1.0),
-            TextFont {
-                font_size: 20.0,
-                ..default()
+fn animate_dino(
+    time: Res<Time>,
+    state: Res<State<GameState>>,
+    assets: Res<GameAssets>,
+    mut query: Query<(&mut DinoAnimation, &mut Sprite, &Player)>,
+) {
+    for (mut animation, mut sprite, player) in query.iter_mut() {
+        let clip = match state.get() {
+            GameState::Menu | GameState::Editor => DinoClip::Idle,
+            _ if player.crashed => DinoClip::Dead,
+            // 游戏结束时没撞车的恐龙（幽灵）停在当前帧
+            GameState::GameOver => continue,
+            GameState::Playing if player.is_jumping => {
+                if player.velocity_y > 0.0 {
+                    DinoClip::Jump
+                } else {
+                    DinoClip::Fall
+                }
+            }
+            // 刚从空中落地时先压扁一下，播完再回到跑步
+            GameState::Playing => match animation.clip {
+                DinoClip::Jump | DinoClip::Fall => DinoClip::Land,
+                DinoClip::Land if !assets.dino_clip(DinoClip::Land).finished(animation.elapsed) => DinoClip::Land,
+                _ if player.ducking => DinoClip::Duck,
+                _ => DinoClip::Run,
             },
-        ));
-
-        // 重置输入状态，避免立即再次重启
-        input_state.space_just_pressed = false;
-        input_state.space_pressed = false;
-
-        // 重置障碍物计时器
-        obstacle_timer.0.set_duration(std::time::Duration::from_secs_f32(2.0));
-        obstacle_timer.0.reset();
+        };
+        animation.play(clip);
+        animation.elapsed += time.delta_secs();
 
-        next_state.set(GameState::Playing);
+        let index = assets.dino_clip(animation.clip).frame_at(animation.elapsed);
+        if let Some(atlas) = sprite.texture_atlas.as_mut() {
+            atlas.index = index;
+        }
     }
 }
 
-fn animate_dino(
-    time: Res<Time>,
-    mut query: Query<(
-        &mut AnimationTimer,
-        &mut DinoAnimation,
-        &mut Sprite,
-        &Player,
-    )>,
+// 切换主题后让场上的恐龙和地面换上新贴图
+fn refresh_theme_sprites(
+    assets: Res<GameAssets>,
+    mut dino_query: Query<&mut Sprite, With<DinoAnimation>>,
+    mut ground_query: Query<&mut Sprite, (With<Ground>, Without<DinoAnimation>)>,
 ) {
-    for (mut timer, mut animation, mut sprite, player) in query.iter_mut() {
-        // 只有在地面上才播放跑步动画
-        if !player.is_jumping {
-            timer.0.tick(time.delta());
-
-            if timer.0.just_finished() {
-                // 切换到下一帧
-                animation.current_frame = (animation.current_frame + 1) % animation.frames.len();
-                sprite.image = animation.frames[animation.current_frame].clone();
-            }
-        } else {
-            // 跳跃时固定在第一帧
-            animation.current_frame = 0;
-            sprite.image = animation.frames[0].clone();
+    for mut sprite in dino_query.iter_mut() {
+        sprite.image = assets.dino_sheet.clone();
+        if let Some(atlas) = sprite.texture_atlas.as_mut() {
+            atlas.layout = assets.dino_layout.clone();
         }
     }
+    for mut sprite in ground_query.iter_mut() {
+        sprite.image = assets.ground_texture.clone();
+    }
 }
 
 // 性能监控系统
@@ -633,13 +1325,45 @@ fn update_fps_display(
 // 显示游戏结束屏幕
 fn show_game_over_screen(
     mut commands: Commands,
+    players: Res<LocalPlayers>,
+    net_session: Option<Res<netplay::NetSession>>,
+    course_mode: Res<courses::CourseMode>,
+    score_query: Query<(&GameScore, &Lane)>,
     game_over_query: Query<Entity, With<GameOverText>>,
 ) {
     // 如果还没有游戏结束文本，就创建一个
     if game_over_query.is_empty() {
+        let message = if course_mode.finished {
+            "Course complete! Press SPACE to play again".to_string()
+        } else if course_mode.resuming() && players.count == 1 {
+            "Game Over! Press SPACE to retry from checkpoint".to_string()
+        } else if players.count == 1 {
+            "Game Over! Press SPACE to restart".to_string()
+        } else {
+            // 双人模式：比较两人的分数决出胜者
+            let mut scores = [0; MAX_PLAYERS];
+            for (score, lane) in score_query.iter() {
+                scores[lane.0] = score.value;
+            }
+            let result = match scores[0].cmp(&scores[1]) {
+                std::cmp::Ordering::Greater => "Player 1 wins!",
+                std::cmp::Ordering::Less => "Player 2 wins!",
+                std::cmp::Ordering::Equal => "Draw!",
+            };
+            format!("{} {} : {}\nPress SPACE or UP to restart", result, scores[0], scores[1])
+        };
+        // 联机时没有菜单；关卡模式可以回到编辑器
+        let message = if net_session.is_some() {
+            message
+        } else if course_mode.selected.is_some() {
+            message + "\nESC: menu  T: theme  E: editor"
+        } else {
+            message + "\nESC: menu  T: theme"
+        };
+
         commands.spawn((
             GameOverText,
-            Text2d::new("Game Over! Press SPACE to restart"),
+            Text2d::new(message),
             Transform::from_xyz(0.0, 0.0, 10.0),
             TextFont {
                 font_size: 30.0,
@@ -0,0 +1,244 @@
+// 开始菜单：恐龙站在原地待机，按跳跃键开局；游戏结束后按 ESC 回到这里
+
+use bevy::prelude::*;
+use dino_game::settings::ScoreMode;
+
+use crate::challenge::DailyChallenge;
+use crate::courses::CourseMode;
+use crate::skin::{ActiveTheme, UserSettings};
+use crate::{GameState, InputState};
+
+#[derive(Component)]
+struct MenuText;
+
+// 菜单当前显示的页面；子页面由各自的模块绘制，ESC 回到主页面
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuPage {
+    #[default]
+    Main,
+    Leaderboard,
+    Achievements,
+    Stats,
+}
+
+// 主页面的快捷键只在主页面生效；游戏结束画面不会切换页面，始终视为主页面
+pub fn main_page(page: Res<MenuPage>) -> bool {
+    *page == MenuPage::Main
+}
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuPage>()
+            .add_systems(OnEnter(GameState::Menu), spawn_menu)
+            .add_systems(OnExit(GameState::Menu), despawn_menu)
+            .add_systems(Update, (close_page, update_menu_text).run_if(in_state(GameState::Menu)))
+            .add_systems(
+                Update,
+                (
+                    start_game.after(crate::handle_input),
+                    toggle_reduced_effects,
+                    toggle_screen_shake,
+                    toggle_score_mode,
+                    toggle_lives_mode,
+                    cycle_difficulty,
+                    cycle_course,
+                    toggle_daily,
+                    adjust_audio,
+                )
+                    .run_if(in_state(GameState::Menu).and(main_page)),
+            )
+            .add_systems(Update, open_menu.run_if(in_state(GameState::GameOver).and(not(crate::leaderboards::typing))));
+    }
+}
+
+fn spawn_menu(mut commands: Commands) {
+    commands.spawn((
+        MenuText,
+        Text2d::new(""),
+        Transform::from_xyz(0.0, 40.0, 10.0),
+        TextFont {
+            font_size: 26.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.33, 0.33, 0.33)),
+    ));
+}
+
+fn despawn_menu(mut commands: Commands, mut page: ResMut<MenuPage>, query: Query<Entity, With<MenuText>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+    page.set_if_neq(MenuPage::Main);
+}
+
+fn close_page(keyboard_input: Res<ButtonInput<KeyCode>>, mut page: ResMut<MenuPage>) {
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        page.set_if_neq(MenuPage::Main);
+    }
+}
+
+fn update_menu_text(
+    theme: Res<ActiveTheme>,
+    settings: Res<UserSettings>,
+    course_mode: Res<CourseMode>,
+    daily: Res<DailyChallenge>,
+    page: Res<MenuPage>,
+    mut query: Query<(&mut Text2d, &mut Visibility), With<MenuText>>,
+) {
+    let best = daily.best().map_or(String::from("-"), |best| best.to_string());
+    let text = format!(
+        "DINO RUN\nPress SPACE to start\nC: course ({})  E: editor  V: leaderboard  A: achievements  S: stats\nY: daily {} ({}, best {})\nD: difficulty ({})\nT: theme ({})\nR: reduced effects ({})\nH: screen shake ({})\nP: scoring ({})\nL: lives mode ({})\nM: mute ({})  B: music ({})  -/+: volume {:.0}%",
+        course_mode.label(),
+        daily.date,
+        on_off(daily.active),
+        best,
+        settings.0.difficulty.name(),
+        theme.0.id,
+        on_off(settings.0.reduced_effects),
+        on_off(settings.0.shake_strength > 0.0),
+        settings.0.score_mode.name(),
+        on_off(settings.0.lives_mode),
+        on_off(settings.0.muted),
+        on_off(settings.0.music),
+        settings.0.volume * 100.0
+    );
+    // 打开子页面时隐藏主菜单
+    let visibility = if *page == MenuPage::Main { Visibility::Inherited } else { Visibility::Hidden };
+    for (mut menu_text, mut menu_visibility) in query.iter_mut() {
+        if menu_text.0 != text {
+            menu_text.0 = text.clone();
+        }
+        menu_visibility.set_if_neq(visibility);
+    }
+}
+
+fn on_off(enabled: bool) -> &'static str {
+    if enabled {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+fn start_game(mut input_state: ResMut<InputState>, mut next_state: ResMut<NextState<GameState>>) {
+    if input_state.any_just_pressed() {
+        // 开局的这一下不算跳跃；保留按住状态，松开前不会再触发
+        for button in input_state.players.iter_mut() {
+            button.just_pressed = false;
+        }
+        next_state.set(GameState::Playing);
+    }
+}
+
+fn toggle_reduced_effects(keyboard_input: Res<ButtonInput<KeyCode>>, mut settings: ResMut<UserSettings>) {
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        settings.0.reduced_effects = !settings.0.reduced_effects;
+        if let Err(e) = settings.0.save() {
+            warn!("保存设置失败: {}", e);
+        }
+    }
+}
+
+// 开关镜头抖动，设置文件里可以写其他强度
+fn toggle_screen_shake(keyboard_input: Res<ButtonInput<KeyCode>>, mut settings: ResMut<UserSettings>) {
+    if keyboard_input.just_pressed(KeyCode::KeyH) {
+        settings.0.shake_strength = if settings.0.shake_strength > 0.0 { 0.0 } else { 1.0 };
+        if let Err(e) = settings.0.save() {
+            warn!("保存设置失败: {}", e);
+        }
+    }
+}
+
+// 在按障碍物计分和按距离计分之间切换
+fn toggle_score_mode(keyboard_input: Res<ButtonInput<KeyCode>>, mut settings: ResMut<UserSettings>) {
+    if keyboard_input.just_pressed(KeyCode::KeyP) {
+        settings.0.score_mode = match settings.0.score_mode {
+            ScoreMode::Obstacles => ScoreMode::Distance,
+            ScoreMode::Distance => ScoreMode::Obstacles,
+        };
+        if let Err(e) = settings.0.save() {
+            warn!("保存设置失败: {}", e);
+        }
+    }
+}
+
+fn cycle_difficulty(keyboard_input: Res<ButtonInput<KeyCode>>, mut settings: ResMut<UserSettings>) {
+    if keyboard_input.just_pressed(KeyCode::KeyD) {
+        settings.0.difficulty = settings.0.difficulty.next();
+        if let Err(e) = settings.0.save() {
+            warn!("保存设置失败: {}", e);
+        }
+    }
+}
+
+// 在无尽模式和各个关卡之间轮换，关卡列表在启动时读取；选中关卡时关闭每日挑战
+fn cycle_course(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut course_mode: ResMut<CourseMode>,
+    mut daily: ResMut<DailyChallenge>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyC) {
+        course_mode.cycle();
+        if course_mode.selected.is_some() {
+            daily.active = false;
+        }
+    }
+}
+
+// 开关每日挑战；每日挑战是无尽模式，打开时取消选中的关卡
+fn toggle_daily(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut course_mode: ResMut<CourseMode>,
+    mut daily: ResMut<DailyChallenge>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyY) {
+        daily.active = !daily.active;
+        if daily.active {
+            course_mode.selected = None;
+        }
+    }
+}
+
+fn toggle_lives_mode(keyboard_input: Res<ButtonInput<KeyCode>>, mut settings: ResMut<UserSettings>) {
+    if keyboard_input.just_pressed(KeyCode::KeyL) {
+        settings.0.lives_mode = !settings.0.lives_mode;
+        if let Err(e) = settings.0.save() {
+            warn!("保存设置失败: {}", e);
+        }
+    }
+}
+
+// M 静音，B 开关音乐，-/+ 调整音量
+fn adjust_audio(keyboard_input: Res<ButtonInput<KeyCode>>, mut settings: ResMut<UserSettings>) {
+    let pressed = |keys: &[KeyCode]| keyboard_input.any_just_pressed(keys.iter().copied());
+    let volume = settings.0.volume;
+    // 只在按键时才修改设置，避免每帧都触发变更检测
+    if pressed(&[KeyCode::KeyM]) {
+        settings.0.muted = !settings.0.muted;
+    } else if pressed(&[KeyCode::KeyB]) {
+        settings.0.music = !settings.0.music;
+    } else if pressed(&[KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        settings.0.volume = ((volume - 0.1) * 10.0).round().max(0.0) / 10.0;
+    } else if pressed(&[KeyCode::Equal, KeyCode::NumpadAdd]) {
+        settings.0.volume = ((volume + 0.1) * 10.0).round().min(10.0) / 10.0;
+    } else {
+        return;
+    }
+
+    if let Err(e) = settings.0.save() {
+        warn!("保存设置失败: {}", e);
+    }
+}
+
+// 联机时没有菜单
+fn open_menu(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    net_session: Option<Res<crate::netplay::NetSession>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if net_session.is_none() && keyboard_input.just_pressed(KeyCode::Escape) {
+        next_state.set(GameState::Menu);
+    }
+}
@@ -0,0 +1,197 @@
+// 联机对战：两台机器通过锁步协议同步每个 tick 的跳跃输入，对手显示为半透明恐龙
+// 联机时游戏时间固定为每帧 1/60 秒，等待对方输入时暂停，保证双方模拟完全一致
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::{TimeSystem, TimeUpdateStrategy};
+use dino_game::lockstep::{Lockstep, Role};
+use rand::prelude::*;
+
+use crate::{ai, daynight, handle_input, player_input, restart_game, GameRng, GameState, InputState, Lane, Player};
+
+// 卡顿超过这么多帧才提示正在等待对手，避免偶发丢包时文字闪烁
+const STALL_NOTICE_FRAMES: u32 = 20;
+
+#[derive(Resource)]
+pub struct NetSession {
+    pub lockstep: Lockstep,
+    pub local_lane: Lane,
+    seeded_round: Option<u32>,     // 已用种子重置过随机数的回合
+    pending_jump: bool,            // 还没写入 tick 的本地跳跃
+    tick_inputs: Option<[bool; 2]>, // 本帧推进的 tick 的双方输入
+    stalled_frames: u32,
+}
+
+impl NetSession {
+    pub fn new(lockstep: Lockstep) -> Self {
+        let local_lane = match lockstep.role() {
+            Role::Host => Lane(0),
+            Role::Client => Lane(1),
+        };
+        Self {
+            lockstep,
+            local_lane,
+            seeded_round: None,
+            pending_jump: false,
+            tick_inputs: None,
+            stalled_frames: 0,
+        }
+    }
+}
+
+#[derive(Component)]
+struct NetStatusText;
+
+pub struct NetPlugin;
+
+impl Plugin for NetPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(1.0 / 60.0)))
+            .add_systems(Startup, spawn_net_status)
+            .add_systems(First, net_tick.before(TimeSystem))
+            .add_systems(
+                Update,
+                (
+                    apply_net_inputs
+                        .after(handle_input)
+                        .after(ai::ai_player_input)
+                        .before(player_input)
+                        .before(restart_game),
+                    tint_opponent,
+                    update_net_status,
+                ),
+            );
+    }
+}
+
+fn spawn_net_status(mut commands: Commands) {
+    commands.spawn((
+        NetStatusText,
+        Text2d::new(""),
+        Transform::from_xyz(0.0, 100.0, 10.0),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+        daynight::HudText,
+    ));
+}
+
+// 在时间更新之前收发数据包，决定这一帧能否推进一个 tick
+fn net_tick(
+    mut session: ResMut<NetSession>,
+    mut rng: ResMut<GameRng>,
+    mut time: ResMut<Time<Virtual>>,
+    state: Res<State<GameState>>,
+) {
+    let session = &mut *session;
+    if let Err(e) = session.lockstep.poll() {
+        warn!("联机数据接收失败: {}", e);
+    }
+
+    session.tick_inputs = None;
+    if *state.get() == GameState::Playing && session.lockstep.is_connected() {
+        // 新回合开始时双方用同一个种子重置障碍物随机数
+        let round = session.lockstep.round();
+        if session.seeded_round != Some(round) {
+            if let Some(seed) = session.lockstep.round_seed() {
+                rng.0 = StdRng::seed_from_u64(seed);
+            }
+            session.seeded_round = Some(round);
+        }
+
+        if session.lockstep.push_local_input(session.pending_jump) {
+            session.pending_jump = false;
+        }
+        session.tick_inputs = session.lockstep.try_advance();
+    }
+    session.lockstep.send();
+
+    // 没有可推进的 tick 时暂停游戏时间
+    if session.tick_inputs.is_some() {
+        session.stalled_frames = 0;
+        time.unpause();
+    } else {
+        session.stalled_frames += 1;
+        time.pause();
+    }
+}
+
+// 用锁步输入覆盖本地键盘输入；游戏结束后双方都按下跳跃才重新开始
+fn apply_net_inputs(
+    mut session: ResMut<NetSession>,
+    mut input_state: ResMut<InputState>,
+    state: Res<State<GameState>>,
+) {
+    // 本地任意跳跃键都算作自己的输入
+    let local_pressed = input_state.any_just_pressed();
+    for button in input_state.players.iter_mut() {
+        button.just_pressed = false;
+    }
+
+    match state.get() {
+        GameState::Playing => {
+            session.pending_jump |= local_pressed;
+            if let Some(inputs) = session.tick_inputs {
+                for (button, jump) in input_state.players.iter_mut().zip(inputs) {
+                    button.just_pressed = jump;
+                }
+            }
+        }
+        GameState::GameOver => {
+            if local_pressed {
+                session.lockstep.ready_for_next_round();
+            }
+            // 回合号变化说明双方都已准备好，交给 restart_game 重开
+            if session.seeded_round.is_some_and(|round| round != session.lockstep.round()) {
+                input_state.players[session.local_lane.0].just_pressed = true;
+            }
+        }
+        // 联机时不会进入菜单和编辑器
+        GameState::Menu | GameState::Editor => {}
+    }
+}
+
+// 对手画成半透明并放在本地玩家后面
+fn tint_opponent(
+    session: Res<NetSession>,
+    mut query: Query<(&Lane, &mut Sprite, &mut Transform), Added<Player>>,
+) {
+    for (lane, mut sprite, mut transform) in query.iter_mut() {
+        if *lane != session.local_lane {
+            sprite.color = Color::srgba(1.0, 1.0, 1.0, 0.4);
+            transform.translation.z = 0.5;
+        }
+    }
+}
+
+fn update_net_status(
+    session: Res<NetSession>,
+    state: Res<State<GameState>>,
+    mut text_query: Query<&mut Text2d, With<NetStatusText>>,
+) {
+    let status = if !session.lockstep.is_connected() {
+        match session.lockstep.role() {
+            Role::Host => format!(
+                "Waiting for opponent on port {}...",
+                session.lockstep.local_addr().map_or(0, |addr| addr.port())
+            ),
+            Role::Client => "Connecting...".to_string(),
+        }
+    } else if *state.get() == GameState::GameOver && session.lockstep.is_ready() {
+        "Waiting for opponent to restart...".to_string()
+    } else if *state.get() == GameState::Playing && session.stalled_frames > STALL_NOTICE_FRAMES {
+        "Waiting for opponent...".to_string()
+    } else if *state.get() == GameState::Playing && session.lockstep.next_tick() < 120 {
+        format!("You are P{}", session.local_lane.0 + 1)
+    } else {
+        String::new()
+    };
+
+    for mut text in text_query.iter_mut() {
+        if text.0 != status {
+            text.0 = status.clone();
+        }
+    }
+}
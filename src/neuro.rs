@@ -0,0 +1,299 @@
+// 神经进化：一个很小的前馈网络控制恐龙是否起跳
+// 网络结构固定为 传感器 → 隐藏层(tanh) → 单输出，基因组就是全部权重
+
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+
+use rand::prelude::*;
+
+use crate::sim::{Simulation, SENSOR_COUNT};
+
+pub const HIDDEN_COUNT: usize = 8;
+pub const GENOME_LEN: usize = SENSOR_COUNT * HIDDEN_COUNT + HIDDEN_COUNT + HIDDEN_COUNT + 1;
+
+// 基因组文件头，用于校验网络结构是否匹配
+const GENOME_HEADER: &str = "dino-genome v1";
+
+// 模拟使用的固定步长，与游戏目标帧率一致
+pub const SIM_DT: f32 = 1.0 / 60.0;
+
+#[derive(Debug, Clone)]
+pub struct Genome {
+    pub weights: Vec<f32>,
+}
+
+impl Genome {
+    pub fn random(rng: &mut impl Rng) -> Self {
+        Self {
+            weights: (0..GENOME_LEN).map(|_| rng.random_range(-1.0..1.0)).collect(),
+        }
+    }
+
+    // 前向计算，输出大于 0 表示起跳
+    pub fn output(&self, sensors: &[f32; SENSOR_COUNT]) -> f32 {
+        let (input_weights, rest) = self.weights.split_at(SENSOR_COUNT * HIDDEN_COUNT);
+        let (hidden_bias, rest) = rest.split_at(HIDDEN_COUNT);
+        let (output_weights, output_bias) = rest.split_at(HIDDEN_COUNT);
+
+        let mut output = output_bias[0];
+        for h in 0..HIDDEN_COUNT {
+            let row = &input_weights[h * SENSOR_COUNT..(h + 1) * SENSOR_COUNT];
+            let sum: f32 = row.iter().zip(sensors).map(|(w, s)| w * s).sum();
+            output += output_weights[h] * (sum + hidden_bias[h]).tanh();
+        }
+        output
+    }
+
+    pub fn wants_jump(&self, sensors: &[f32; SENSOR_COUNT]) -> bool {
+        self.output(sensors) > 0.0
+    }
+
+    // 高斯扰动，每个权重以 rate 的概率变异
+    pub fn mutate(&mut self, rng: &mut impl Rng, rate: f32, strength: f32) {
+        for weight in self.weights.iter_mut() {
+            if rng.random::<f32>() < rate {
+                *weight += gaussian(rng) * strength;
+            }
+        }
+    }
+
+    // 均匀交叉
+    pub fn crossover(a: &Genome, b: &Genome, rng: &mut impl Rng) -> Genome {
+        Genome {
+            weights: a
+                .weights
+                .iter()
+                .zip(&b.weights)
+                .map(|(&wa, &wb)| if rng.random_bool(0.5) { wa } else { wb })
+                .collect(),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut content = format!("{} {} {}\n", GENOME_HEADER, SENSOR_COUNT, HIDDEN_COUNT);
+        for weight in &self.weights {
+            let _ = writeln!(content, "{}", weight);
+        }
+        std::fs::write(path, content)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Genome> {
+        let content = std::fs::read_to_string(path)?;
+        let mut lines = content.lines();
+
+        let expected_header = format!("{} {} {}", GENOME_HEADER, SENSOR_COUNT, HIDDEN_COUNT);
+        if lines.next().map(str::trim) != Some(expected_header.as_str()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "基因组文件头不匹配，网络结构可能已变化",
+            ));
+        }
+
+        let weights = lines
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                line.trim()
+                    .parse::<f32>()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            })
+            .collect::<io::Result<Vec<f32>>>()?;
+        if weights.len() != GENOME_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("权重数量应为 {}，实际为 {}", GENOME_LEN, weights.len()),
+            ));
+        }
+
+        Ok(Genome { weights })
+    }
+}
+
+// Box-Muller 变换生成标准正态分布随机数
+fn gaussian(rng: &mut impl Rng) -> f32 {
+    let u1: f32 = rng.random_range(f32::EPSILON..1.0);
+    let u2: f32 = rng.random();
+    (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct EpisodeResult {
+    pub score: u32,
+    pub survival_time: f32,
+}
+
+impl EpisodeResult {
+    // 适应度：分数为主，存活时间为辅，让早期还不会跳的个体也能区分优劣
+    pub fn fitness(&self) -> f32 {
+        self.score as f32 * 10.0 + self.survival_time
+    }
+}
+
+// 用给定基因组控制恐龙跑完一局，max_time 防止完美个体无限运行
+pub fn run_episode(genome: &Genome, seed: u64, max_time: f32) -> EpisodeResult {
+    let mut sim = Simulation::new(seed);
+    while !sim.crashed && sim.elapsed < max_time {
+        let jump = genome.wants_jump(&sim.sensors());
+        sim.step(SIM_DT, jump);
+    }
+    EpisodeResult {
+        score: sim.score,
+        survival_time: sim.elapsed,
+    }
+}
+
+// 进化参数
+#[derive(Debug, Clone)]
+pub struct EvolutionConfig {
+    pub population: usize,
+    pub elite: usize,
+    pub tournament: usize,
+    pub mutation_rate: f32,
+    pub mutation_strength: f32,
+}
+
+impl Default for EvolutionConfig {
+    fn default() -> Self {
+        Self {
+            population: 100,
+            elite: 10,
+            tournament: 5,
+            mutation_rate: 0.2,
+            mutation_strength: 0.5,
+        }
+    }
+}
+
+pub struct Population {
+    pub genomes: Vec<Genome>,
+    pub generation: u32,
+    config: EvolutionConfig,
+    rng: StdRng,
+}
+
+impl Population {
+    pub fn new(config: EvolutionConfig, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let genomes = (0..config.population).map(|_| Genome::random(&mut rng)).collect();
+        Self {
+            genomes,
+            generation: 0,
+            config,
+            rng,
+        }
+    }
+
+    // 用已有基因组作为种子继续训练，其余个体由它变异而来
+    pub fn from_genome(config: EvolutionConfig, genome: Genome, seed: u64) -> Self {
+        let mut population = Self::new(config, seed);
+        for (i, slot) in population.genomes.iter_mut().enumerate() {
+            *slot = genome.clone();
+            if i > 0 {
+                let (rate, strength) = (population.config.mutation_rate, population.config.mutation_strength);
+                slot.mutate(&mut population.rng, rate, strength);
+            }
+        }
+        population
+    }
+
+    // 根据适应度生成下一代：精英直接保留，其余由锦标赛选择 + 交叉 + 变异产生
+    pub fn evolve(&mut self, fitness: &[f32]) {
+        assert_eq!(fitness.len(), self.genomes.len());
+
+        let mut ranked: Vec<usize> = (0..self.genomes.len()).collect();
+        ranked.sort_by(|&a, &b| fitness[b].total_cmp(&fitness[a]));
+
+        let mut next = Vec::with_capacity(self.config.population);
+        for &index in ranked.iter().take(self.config.elite) {
+            next.push(self.genomes[index].clone());
+        }
+
+        while next.len() < self.config.population {
+            let a = self.tournament(fitness);
+            let b = self.tournament(fitness);
+            let mut child = Genome::crossover(&self.genomes[a], &self.genomes[b], &mut self.rng);
+            child.mutate(&mut self.rng, self.config.mutation_rate, self.config.mutation_strength);
+            next.push(child);
+        }
+
+        self.genomes = next;
+        self.generation += 1;
+    }
+
+    fn tournament(&mut self, fitness: &[f32]) -> usize {
+        let mut best = self.rng.random_range(0..self.genomes.len());
+        for _ in 1..self.config.tournament {
+            let candidate = self.rng.random_range(0..self.genomes.len());
+            if fitness[candidate] > fitness[best] {
+                best = candidate;
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 每个测试用自己的文件名，并行运行时互不干扰
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("dino-genome-test-{}-{}.txt", std::process::id(), name))
+    }
+
+    #[test]
+    fn save_load_round_trip() {
+        let genome = Genome::random(&mut StdRng::seed_from_u64(7));
+        let path = temp_path("round-trip");
+        genome.save(&path).unwrap();
+        let loaded = Genome::load(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(loaded.unwrap().weights, genome.weights);
+    }
+
+    #[test]
+    fn load_rejects_mismatched_structure() {
+        let path = temp_path("mismatch");
+        std::fs::write(&path, format!("{} {} {}\n0.5\n", GENOME_HEADER, SENSOR_COUNT, HIDDEN_COUNT + 1)).unwrap();
+        let wrong_header = Genome::load(&path);
+        std::fs::write(&path, format!("{} {} {}\n0.5\n", GENOME_HEADER, SENSOR_COUNT, HIDDEN_COUNT)).unwrap();
+        let wrong_count = Genome::load(&path);
+        let _ = std::fs::remove_file(&path);
+        assert!(wrong_header.is_err());
+        assert!(wrong_count.is_err());
+    }
+
+    #[test]
+    fn evolve_keeps_size_and_elites() {
+        let config = EvolutionConfig {
+            population: 20,
+            elite: 3,
+            ..EvolutionConfig::default()
+        };
+        let mut population = Population::new(config, 1);
+        let fitness: Vec<f32> = (0..20).map(|i| i as f32).collect();
+        let best: Vec<Vec<f32>> = [19, 18, 17].iter().map(|&i| population.genomes[i].weights.clone()).collect();
+
+        population.evolve(&fitness);
+        assert_eq!(population.generation, 1);
+        assert_eq!(population.genomes.len(), 20);
+        for (genome, elite) in population.genomes.iter().zip(&best) {
+            assert_eq!(&genome.weights, elite);
+        }
+        assert!(population.genomes.iter().all(|genome| genome.weights.len() == GENOME_LEN));
+    }
+
+    #[test]
+    fn evolution_is_reproducible_from_seed() {
+        let run = || {
+            let mut population = Population::new(EvolutionConfig::default(), 3);
+            for _ in 0..3 {
+                let fitness: Vec<f32> =
+                    population.genomes.iter().map(|genome| run_episode(genome, 5, 10.0).fitness()).collect();
+                population.evolve(&fitness);
+            }
+            population.genomes.into_iter().map(|genome| genome.weights).collect::<Vec<_>>()
+        };
+        assert_eq!(run(), run());
+    }
+}
@@ -0,0 +1,239 @@
+// 视差滚动背景：远山、云朵、星星等多层背景以世界速度的不同比例滚动
+// 图层颜色跟随昼夜配色变化，月亮和星星只在夜晚显现
+// 每层的元素数量固定，滚出屏幕左侧后重新随机大小和高度，放到该层最右边继续使用
+
+use bevy::prelude::*;
+use dino_game::sim::{GROUND_Y, WINDOW_WIDTH};
+use rand::prelude::*;
+
+use crate::daynight::{blend_palette, CurrentPalette, DayNight};
+use crate::{GameState, WorldSpeed};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParallaxShape {
+    Hill,  // 弓形，弦贴着地面
+    Cloud, // 扁椭圆
+    Star,  // 小圆点
+    Moon,  // 圆盘
+}
+
+#[derive(Debug, Clone)]
+pub struct ParallaxLayer {
+    pub shape: ParallaxShape,
+    pub speed_factor: f32, // 相对世界速度的比例，越远越小
+    pub z: f32,
+    pub color: Color,
+    pub y_range: (f32, f32),       // 元素中心高度范围（远山为山顶离地面的高度）
+    pub size_range: (f32, f32),    // 元素宽度范围
+    pub spacing_range: (f32, f32), // 相邻元素的水平间距范围
+    pub night_only: bool,          // 只在夜晚显现，不受背景色调影响
+}
+
+// 背景配置，主题可以替换整组图层；修改后背景会自动重建
+#[derive(Resource, Debug, Clone)]
+pub struct ParallaxConfig {
+    pub layers: Vec<ParallaxLayer>,
+}
+
+impl Default for ParallaxConfig {
+    fn default() -> Self {
+        Self {
+            layers: vec![
+                ParallaxLayer {
+                    shape: ParallaxShape::Star,
+                    speed_factor: 0.03,
+                    z: -5.0,
+                    color: Color::srgb(1.0, 1.0, 0.9),
+                    y_range: (0.0, 190.0),
+                    size_range: (2.0, 4.0),
+                    spacing_range: (15.0, 70.0),
+                    night_only: true,
+                },
+                ParallaxLayer {
+                    shape: ParallaxShape::Moon,
+                    speed_factor: 0.02,
+                    z: -4.0,
+                    color: Color::srgb(0.95, 0.95, 0.85),
+                    y_range: (120.0, 160.0),
+                    size_range: (36.0, 36.0),
+                    spacing_range: (WINDOW_WIDTH, WINDOW_WIDTH * 1.5), // 屏幕上最多一个月亮
+                    night_only: true,
+                },
+                ParallaxLayer {
+                    shape: ParallaxShape::Hill,
+                    speed_factor: 0.1,
+                    z: -3.0,
+                    color: Color::srgb(0.78, 0.8, 0.76),
+                    y_range: (30.0, 90.0),
+                    size_range: (160.0, 320.0),
+                    spacing_range: (-80.0, 160.0), // 允许相邻的山互相重叠
+                    night_only: false,
+                },
+                ParallaxLayer {
+                    shape: ParallaxShape::Cloud,
+                    speed_factor: 0.25,
+                    z: -2.0,
+                    color: Color::srgb(1.0, 1.0, 1.0),
+                    y_range: (40.0, 150.0),
+                    size_range: (50.0, 100.0),
+                    spacing_range: (120.0, 360.0),
+                    night_only: false,
+                },
+            ],
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct ParallaxItem {
+    pub layer: usize,
+}
+
+// 每层共用一个材质，换色时只需修改这些材质
+#[derive(Resource, Default)]
+struct LayerMaterials(Vec<Handle<ColorMaterial>>);
+
+pub struct ParallaxPlugin;
+
+impl Plugin for ParallaxPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ParallaxConfig>()
+            .init_resource::<LayerMaterials>()
+            .add_systems(
+                Update,
+                (
+                    rebuild_parallax.run_if(resource_changed::<ParallaxConfig>),
+                    tint_layers.run_if(
+                        resource_changed::<ParallaxConfig>.or(resource_changed::<CurrentPalette>),
+                    ),
+                )
+                    .chain()
+                    .after(blend_palette),
+            )
+            .add_systems(Update, scroll_parallax.run_if(in_state(GameState::Playing)));
+    }
+}
+
+// 远山弓形的半角，越大山越圆
+const HILL_HALF_ANGLE: f32 = 1.2;
+
+// 远山弓形的半径，使弦长（即宽度）为 1
+fn hill_radius() -> f32 {
+    0.5 / HILL_HALF_ANGLE.sin()
+}
+
+// 每种形状使用宽度为 1 的网格，通过缩放得到不同尺寸
+fn unit_mesh(shape: ParallaxShape) -> Mesh {
+    match shape {
+        ParallaxShape::Hill => CircularSegment::new(hill_radius(), HILL_HALF_ANGLE).into(),
+        ParallaxShape::Star | ParallaxShape::Moon => Circle::new(0.5).into(),
+        ParallaxShape::Cloud => Ellipse::new(0.5, 0.2).into(),
+    }
+}
+
+// 随机生成一个元素的位置和缩放（x 为左边缘）
+fn random_transform(layer: &ParallaxLayer, left_x: f32, rng: &mut impl Rng) -> Transform {
+    let size = rng.random_range(layer.size_range.0..=layer.size_range.1);
+    let height = rng.random_range(layer.y_range.0..=layer.y_range.1);
+    match layer.shape {
+        ParallaxShape::Hill => {
+            // 网格原点在圆心，弦在原点上方；纵向缩放让弓形高度等于 height，再把弦放到地面上
+            let radius = hill_radius();
+            let apothem = radius * HILL_HALF_ANGLE.cos();
+            let scale_y = height / (radius - apothem);
+            Transform::from_xyz(left_x + size * 0.5, GROUND_Y - apothem * scale_y, layer.z)
+                .with_scale(Vec3::new(size, scale_y, 1.0))
+        }
+        ParallaxShape::Cloud | ParallaxShape::Star | ParallaxShape::Moon => {
+            Transform::from_xyz(left_x + size * 0.5, height, layer.z).with_scale(Vec3::new(size, size, 1.0))
+        }
+    }
+}
+
+fn rebuild_parallax(
+    mut commands: Commands,
+    config: Res<ParallaxConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut layer_materials: ResMut<LayerMaterials>,
+    items: Query<Entity, With<ParallaxItem>>,
+) {
+    for entity in items.iter() {
+        commands.entity(entity).despawn();
+    }
+    layer_materials.0.clear();
+
+    let mut rng = rand::rng();
+    for (index, layer) in config.layers.iter().enumerate() {
+        let mesh = meshes.add(unit_mesh(layer.shape));
+        let material = materials.add(layer.color);
+        layer_materials.0.push(material.clone());
+
+        // 从屏幕左边缘一直铺到右边缘外，保证开局时背景就是满的
+        let mut left_x = -WINDOW_WIDTH / 2.0 - rng.random_range(0.0..=layer.spacing_range.1);
+        while left_x < WINDOW_WIDTH / 2.0 + layer.size_range.1 {
+            let transform = random_transform(layer, left_x, &mut rng);
+            left_x += transform.scale.x + rng.random_range(layer.spacing_range.0..=layer.spacing_range.1);
+            commands.spawn((
+                Mesh2d(mesh.clone()),
+                MeshMaterial2d(material.clone()),
+                transform,
+                ParallaxItem { layer: index },
+            ));
+        }
+    }
+}
+
+// 普通图层乘以当前背景色调，夜晚图层的透明度随昼夜进度变化
+fn tint_layers(
+    config: Res<ParallaxConfig>,
+    palette: Res<CurrentPalette>,
+    day_night: Res<DayNight>,
+    layer_materials: Res<LayerMaterials>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let tint = palette.0.background.to_linear().to_vec4();
+    for (layer, handle) in config.layers.iter().zip(&layer_materials.0) {
+        let Some(material) = materials.get_mut(handle) else {
+            continue;
+        };
+        material.color = if layer.night_only {
+            layer.color.with_alpha(day_night.night)
+        } else {
+            Color::LinearRgba(LinearRgba::from_vec4(layer.color.to_linear().to_vec4() * tint))
+        };
+    }
+}
+
+fn scroll_parallax(
+    time: Res<Time>,
+    speed: Res<WorldSpeed>,
+    config: Res<ParallaxConfig>,
+    mut items: Query<(&ParallaxItem, &mut Transform)>,
+) {
+    let mut rng = rand::rng();
+
+    // 每层最右边元素的右边缘，用于回收时接在后面
+    let mut rightmost = vec![f32::MIN; config.layers.len()];
+    for (item, mut transform) in items.iter_mut() {
+        let Some(layer) = config.layers.get(item.layer) else {
+            continue;
+        };
+        transform.translation.x -= speed.0 * layer.speed_factor * time.delta_secs();
+        let right_edge = transform.translation.x + transform.scale.x * 0.5;
+        rightmost[item.layer] = rightmost[item.layer].max(right_edge);
+    }
+
+    for (item, mut transform) in items.iter_mut() {
+        let Some(layer) = config.layers.get(item.layer) else {
+            continue;
+        };
+        // 完全滚出屏幕左侧后回收到最右边
+        if transform.translation.x + transform.scale.x * 0.5 < -WINDOW_WIDTH / 2.0 {
+            let left_x = rightmost[item.layer].max(WINDOW_WIDTH / 2.0)
+                + rng.random_range(layer.spacing_range.0..=layer.spacing_range.1);
+            *transform = random_transform(layer, left_x, &mut rng);
+            rightmost[item.layer] = transform.translation.x + transform.scale.x * 0.5;
+        }
+    }
+}
@@ -0,0 +1,241 @@
+// 轻量的 CPU 粒子：落地扬尘、起跳气团和撞车碎片
+// 粒子实体在启动时一次性生成放进对象池，发射时取出、熄灭后隐藏并放回，运行中不再生成或销毁实体
+
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::events::{Crashed, Jumped, Landed, ObstacleSmashed};
+use crate::skin::UserSettings;
+
+// 对象池大小，池子用完时新的粒子直接丢弃
+const POOL_SIZE: usize = 128;
+
+// 减少特效时只发射这个比例的粒子
+const REDUCED_FACTOR: f32 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BurstKind {
+    Dust,   // 落地扬尘
+    Puff,   // 起跳气团
+    Debris, // 撞车碎片
+}
+
+// 在指定位置发射一组粒子
+#[derive(Event)]
+pub struct ParticleBurst {
+    pub kind: BurstKind,
+    pub position: Vec2,
+}
+
+// 一种粒子的外观和运动参数
+#[derive(Debug, Clone)]
+pub struct ParticleStyle {
+    pub color: Color,
+    pub count: u32,
+    pub lifetime: f32,
+    pub size: f32,
+    pub speed: (f32, f32),
+    pub angle: (f32, f32), // 发射方向范围（弧度，0 为向右）
+    pub gravity: f32,
+}
+
+// 各类粒子的参数，主题可以覆盖
+#[derive(Resource, Debug, Clone)]
+pub struct ParticleSettings {
+    pub dust: ParticleStyle,
+    pub puff: ParticleStyle,
+    pub debris: ParticleStyle,
+}
+
+impl ParticleSettings {
+    pub fn style_mut(&mut self, kind: BurstKind) -> &mut ParticleStyle {
+        match kind {
+            BurstKind::Dust => &mut self.dust,
+            BurstKind::Puff => &mut self.puff,
+            BurstKind::Debris => &mut self.debris,
+        }
+    }
+
+    fn style(&self, kind: BurstKind) -> &ParticleStyle {
+        match kind {
+            BurstKind::Dust => &self.dust,
+            BurstKind::Puff => &self.puff,
+            BurstKind::Debris => &self.debris,
+        }
+    }
+}
+
+impl Default for ParticleSettings {
+    fn default() -> Self {
+        Self {
+            // 向左右两侧贴地扬起
+            dust: ParticleStyle {
+                color: Color::srgb(0.6, 0.55, 0.5),
+                count: 6,
+                lifetime: 0.35,
+                size: 4.0,
+                speed: (40.0, 90.0),
+                angle: (0.2, std::f32::consts::PI - 0.2),
+                gravity: -200.0,
+            },
+            // 从脚下向后下方散开
+            puff: ParticleStyle {
+                color: Color::srgb(0.85, 0.85, 0.85),
+                count: 4,
+                lifetime: 0.25,
+                size: 5.0,
+                speed: (30.0, 70.0),
+                angle: (std::f32::consts::PI * 0.8, std::f32::consts::PI * 1.3),
+                gravity: 0.0,
+            },
+            // 向四周飞溅后落下
+            debris: ParticleStyle {
+                color: Color::srgb(0.33, 0.33, 0.33),
+                count: 14,
+                lifetime: 0.8,
+                size: 5.0,
+                speed: (120.0, 260.0),
+                angle: (0.0, std::f32::consts::PI),
+                gravity: -900.0,
+            },
+        }
+    }
+}
+
+#[derive(Component)]
+struct Particle {
+    velocity: Vec2,
+    gravity: f32,
+    age: f32,
+    lifetime: f32, // 为 0 表示空闲
+}
+
+#[derive(Resource, Default)]
+struct ParticlePool {
+    free: Vec<Entity>,
+}
+
+pub struct ParticlePlugin;
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ParticleBurst>()
+            .init_resource::<ParticleSettings>()
+            .init_resource::<ParticlePool>()
+            .add_systems(Startup, spawn_pool)
+            .add_systems(Update, (queue_gameplay_bursts, emit_particles, update_particles).chain());
+    }
+}
+
+fn spawn_pool(mut commands: Commands, mut pool: ResMut<ParticlePool>) {
+    for _ in 0..POOL_SIZE {
+        let entity = commands
+            .spawn((
+                Sprite::from_color(Color::WHITE, Vec2::ONE),
+                Transform::from_xyz(0.0, 0.0, 2.0),
+                Visibility::Hidden,
+                Particle {
+                    velocity: Vec2::ZERO,
+                    gravity: 0.0,
+                    age: 0.0,
+                    lifetime: 0.0,
+                },
+            ))
+            .id();
+        pool.free.push(entity);
+    }
+}
+
+// 起跳喷出气团，落地扬起灰尘，撞车和护盾撞碎障碍物时飞出碎片
+fn queue_gameplay_bursts(
+    mut jumped: EventReader<Jumped>,
+    mut landed: EventReader<Landed>,
+    mut crashes: EventReader<Crashed>,
+    mut smashed: EventReader<ObstacleSmashed>,
+    mut bursts: EventWriter<ParticleBurst>,
+) {
+    for event in jumped.read() {
+        bursts.write(ParticleBurst {
+            kind: BurstKind::Puff,
+            position: event.position + Vec2::new(-10.0, -20.0),
+        });
+    }
+    for event in landed.read() {
+        bursts.write(ParticleBurst {
+            kind: BurstKind::Dust,
+            position: event.position,
+        });
+    }
+    let debris = crashes.read().map(|event| event.position).chain(smashed.read().map(|event| event.position));
+    for position in debris {
+        bursts.write(ParticleBurst {
+            kind: BurstKind::Debris,
+            position,
+        });
+    }
+}
+
+fn emit_particles(
+    mut bursts: EventReader<ParticleBurst>,
+    settings: Res<ParticleSettings>,
+    user_settings: Res<UserSettings>,
+    mut pool: ResMut<ParticlePool>,
+    mut query: Query<(&mut Particle, &mut Sprite, &mut Transform, &mut Visibility)>,
+) {
+    let mut rng = rand::rng();
+    for burst in bursts.read() {
+        let style = settings.style(burst.kind);
+        let count = if user_settings.0.reduced_effects {
+            (style.count as f32 * REDUCED_FACTOR).ceil() as u32
+        } else {
+            style.count
+        };
+
+        for _ in 0..count {
+            let Some(entity) = pool.free.pop() else {
+                return;
+            };
+            let Ok((mut particle, mut sprite, mut transform, mut visibility)) = query.get_mut(entity) else {
+                continue;
+            };
+            let angle = rng.random_range(style.angle.0..=style.angle.1);
+            let speed = rng.random_range(style.speed.0..=style.speed.1);
+            particle.velocity = Vec2::from_angle(angle) * speed;
+            particle.gravity = style.gravity;
+            particle.age = 0.0;
+            particle.lifetime = style.lifetime.max(0.01);
+            sprite.color = style.color;
+            sprite.custom_size = Some(Vec2::splat(style.size));
+            transform.translation.x = burst.position.x;
+            transform.translation.y = burst.position.y;
+            *visibility = Visibility::Visible;
+        }
+    }
+}
+
+// 粒子随时间移动并淡出，寿命结束后放回对象池
+fn update_particles(
+    time: Res<Time>,
+    mut pool: ResMut<ParticlePool>,
+    mut query: Query<(Entity, &mut Particle, &mut Sprite, &mut Transform, &mut Visibility)>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut particle, mut sprite, mut transform, mut visibility) in query.iter_mut() {
+        if particle.lifetime <= 0.0 {
+            continue;
+        }
+
+        particle.age += dt;
+        if particle.age >= particle.lifetime {
+            particle.lifetime = 0.0;
+            *visibility = Visibility::Hidden;
+            pool.free.push(entity);
+            continue;
+        }
+
+        particle.velocity.y += particle.gravity * dt;
+        transform.translation += (particle.velocity * dt).extend(0.0);
+        let alpha = 1.0 - particle.age / particle.lifetime;
+        sprite.color.set_alpha(alpha);
+    }
+}
@@ -0,0 +1,208 @@
+// 道具配置：护盾、慢动作、磁铁和二段跳的出现概率、权重与持续时间
+// 数据来自 assets/data/powerups.txt，缺少的条目使用默认值
+
+use std::io;
+use std::path::Path;
+
+pub const POWERUP_FILE: &str = "data/powerups.txt";
+const POWERUP_HEADER: &str = "dino-powerups v1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerUpKind {
+    Shield,     // 抵挡一次撞击
+    SlowMo,     // 地面和障碍物变慢
+    Magnet,     // 吸引附近的金币
+    DoubleJump, // 空中可以再跳一次
+}
+
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 4] = [
+        PowerUpKind::Shield,
+        PowerUpKind::SlowMo,
+        PowerUpKind::Magnet,
+        PowerUpKind::DoubleJump,
+    ];
+
+    // 数据文件里使用的名称
+    pub fn name(self) -> &'static str {
+        match self {
+            PowerUpKind::Shield => "shield",
+            PowerUpKind::SlowMo => "slow_mo",
+            PowerUpKind::Magnet => "magnet",
+            PowerUpKind::DoubleJump => "double_jump",
+        }
+    }
+
+    // HUD 上显示的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            PowerUpKind::Shield => "Shield",
+            PowerUpKind::SlowMo => "Slow-mo",
+            PowerUpKind::Magnet => "Magnet",
+            PowerUpKind::DoubleJump => "Double jump",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerUpTable {
+    pub chance: f32,         // 每个金币位置改放道具的概率
+    pub weights: [f32; 4],   // 按 PowerUpKind::ALL 的顺序
+    pub durations: [f32; 4], // 持续秒数（游戏时间）
+    pub slow_factor: f32,    // 慢动作时的世界速度倍率
+    pub magnet_radius: f32,  // 磁铁吸引金币的距离（像素）
+}
+
+impl Default for PowerUpTable {
+    fn default() -> Self {
+        Self {
+            chance: 0.2,
+            weights: [1.0; 4],
+            durations: [10.0, 5.0, 8.0, 10.0],
+            slow_factor: 0.6,
+            magnet_radius: 160.0,
+        }
+    }
+}
+
+impl PowerUpTable {
+    pub fn load(assets_root: &Path) -> io::Result<PowerUpTable> {
+        PowerUpTable::from_text(&std::fs::read_to_string(assets_root.join(POWERUP_FILE))?)
+    }
+
+    pub fn from_text(content: &str) -> io::Result<PowerUpTable> {
+        let mut lines = content.lines();
+        if lines.next().map(str::trim) != Some(POWERUP_HEADER) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "道具配置文件头不匹配"));
+        }
+
+        let mut table = PowerUpTable::default();
+        for line in lines.map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let value: f32 = value
+                .trim()
+                .parse()
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("无效的数值: {}", line)))?;
+            let value = value.max(0.0);
+
+            match key {
+                "chance" => table.chance = value.min(1.0),
+                "slow_mo.factor" => table.slow_factor = value.clamp(0.1, 1.0),
+                "magnet.radius" => table.magnet_radius = value,
+                _ => {
+                    // 其余条目形如 shield.weight、shield.duration
+                    let Some((name, field)) = key.split_once('.') else {
+                        continue;
+                    };
+                    let Some(index) = PowerUpKind::ALL.iter().position(|kind| kind.name() == name) else {
+                        continue;
+                    };
+                    match field {
+                        "weight" => table.weights[index] = value,
+                        "duration" => table.durations[index] = value,
+                        _ => {}
+                    }
+                }
+            }
+        }
+        Ok(table)
+    }
+
+    // 会出现慢动作道具时世界速度最低能降到的倍率，不会出现时为 1
+    pub fn slowest_speed_factor(&self) -> f32 {
+        if self.chance > 0.0 && self.weights[PowerUpKind::SlowMo as usize] > 0.0 {
+            self.slow_factor
+        } else {
+            1.0
+        }
+    }
+
+    pub fn duration(&self, kind: PowerUpKind) -> f32 {
+        self.durations[kind as usize]
+    }
+
+    // 用两个 0~1 的随机数决定是否放道具以及放哪一种
+    pub fn pick(&self, roll: f32, choice: f32) -> Option<PowerUpKind> {
+        let total: f32 = self.weights.iter().sum();
+        if roll >= self.chance || total <= 0.0 {
+            return None;
+        }
+
+        let mut target = choice * total;
+        for (kind, weight) in PowerUpKind::ALL.iter().zip(self.weights) {
+            if target < weight {
+                return Some(*kind);
+            }
+            target -= weight;
+        }
+        // 浮点误差使 target 落在末尾时，取最后一个权重不为 0 的道具
+        PowerUpKind::ALL
+            .into_iter()
+            .zip(self.weights)
+            .rev()
+            .find(|(_, weight)| *weight > 0.0)
+            .map(|(kind, _)| kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_config_matches_defaults() {
+        let table = PowerUpTable::from_text(include_str!("../assets/data/powerups.txt")).unwrap();
+        assert_eq!(table, PowerUpTable::default());
+    }
+
+    #[test]
+    fn parses_and_clamps_values() {
+        let table = PowerUpTable::from_text(
+            "dino-powerups v1\nchance 3\nslow_mo.factor 0\nmagnet.duration 2.5\nshield.weight -1\nunknown.weight 5\n",
+        )
+        .unwrap();
+        assert_eq!(table.chance, 1.0);
+        assert_eq!(table.slow_factor, 0.1);
+        assert_eq!(table.duration(PowerUpKind::Magnet), 2.5);
+        assert_eq!(table.weights[PowerUpKind::Shield as usize], 0.0);
+    }
+
+    #[test]
+    fn slowest_speed_factor_only_counts_slow_mo_that_can_appear() {
+        let mut table = PowerUpTable::default();
+        assert_eq!(table.slowest_speed_factor(), table.slow_factor);
+        table.weights[PowerUpKind::SlowMo as usize] = 0.0;
+        assert_eq!(table.slowest_speed_factor(), 1.0);
+        table = PowerUpTable {
+            chance: 0.0,
+            ..PowerUpTable::default()
+        };
+        assert_eq!(table.slowest_speed_factor(), 1.0);
+    }
+
+    #[test]
+    fn rejects_wrong_header_and_bad_numbers() {
+        assert!(PowerUpTable::from_text("dino-powerups v2\n").is_err());
+        assert!(PowerUpTable::from_text("dino-powerups v1\nchance lots\n").is_err());
+    }
+
+    #[test]
+    fn pick_follows_chance_and_weights() {
+        let mut table = PowerUpTable {
+            chance: 0.5,
+            weights: [1.0, 0.0, 1.0, 0.0],
+            ..PowerUpTable::default()
+        };
+        assert_eq!(table.pick(0.5, 0.0), None);
+        assert_eq!(table.pick(0.1, 0.0), Some(PowerUpKind::Shield));
+        assert_eq!(table.pick(0.1, 0.6), Some(PowerUpKind::Magnet));
+        // choice 恰好为 1 时落在末尾，取最后一个权重不为 0 的道具
+        assert_eq!(table.pick(0.1, 1.0), Some(PowerUpKind::Magnet));
+
+        table.weights = [0.0; 4];
+        assert_eq!(table.pick(0.0, 0.5), None);
+    }
+}
@@ -0,0 +1,215 @@
+// 道具：护盾、慢动作、磁铁和二段跳
+// 道具代替金币出现在障碍物之间的空中，吃到后在玩家身上计时；
+// 护盾和二段跳分别在碰撞检测和跳跃输入中生效，磁铁和慢动作由本模块处理
+
+use bevy::prelude::*;
+use dino_game::powerup::{PowerUpKind, PowerUpTable};
+
+use crate::{events, skin, Collectible, GameState, Lane, Player, Velocity};
+
+const PICKUP_SIZE: f32 = 20.0;
+// 被磁铁吸引的金币飞向玩家的速度（像素/秒）
+const MAGNET_SPEED: f32 = 600.0;
+
+// 道具配置，联机时出现概率为 0
+#[derive(Resource)]
+pub struct PowerUps(pub PowerUpTable);
+
+// 场上可以拾取的道具
+#[derive(Component)]
+pub struct PowerUpPickup(pub PowerUpKind);
+
+// 玩家身上生效中的道具；幽灵没有该组件
+#[derive(Component, Default)]
+pub struct ActivePowerUps {
+    remaining: [f32; 4],  // 按 PowerUpKind::ALL 的顺序，剩余秒数
+    pub air_jumped: bool, // 这次腾空是否已经用过二段跳
+}
+
+impl ActivePowerUps {
+    pub fn is_active(&self, kind: PowerUpKind) -> bool {
+        self.remaining[kind as usize] > 0.0
+    }
+
+    pub fn consume(&mut self, kind: PowerUpKind) {
+        self.remaining[kind as usize] = 0.0;
+    }
+}
+
+// 分数下方显示生效中的道具和剩余时间
+#[derive(Component)]
+pub struct PowerUpText;
+
+pub struct PowerUpPlugin {
+    pub enabled: bool, // 联机时关闭：慢动作会改变两边共用的世界速度
+}
+
+// 当前的世界速度倍率，任意一名玩家处于慢动作时为配置的倍率，否则为 1
+#[derive(Resource)]
+pub struct SlowMo(pub f32);
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        // 文件不存在或损坏时使用默认配置
+        let mut table = PowerUpTable::load(&skin::assets_root()).unwrap_or_else(|e| {
+            warn!("无法加载道具配置: {}，使用默认配置", e);
+            PowerUpTable::default()
+        });
+        if !self.enabled {
+            table.chance = 0.0;
+        }
+
+        app.insert_resource(PowerUps(table))
+            .insert_resource(SlowMo(1.0))
+            .add_systems(
+                Update,
+                (
+                    tick_powerups,
+                    collect_powerups,
+                    attract_coins.before(crate::collect_coins),
+                    apply_slow_mo,
+                    update_powerup_text,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), reset_slow_mo);
+    }
+}
+
+fn pickup_color(kind: PowerUpKind) -> Color {
+    match kind {
+        PowerUpKind::Shield => Color::srgb(0.3, 0.6, 1.0),
+        PowerUpKind::SlowMo => Color::srgb(0.6, 0.4, 0.9),
+        PowerUpKind::Magnet => Color::srgb(0.9, 0.25, 0.25),
+        PowerUpKind::DoubleJump => Color::srgb(0.3, 0.8, 0.4),
+    }
+}
+
+// 道具方块上的字母
+fn pickup_letter(kind: PowerUpKind) -> &'static str {
+    match kind {
+        PowerUpKind::Shield => "S",
+        PowerUpKind::SlowMo => "T",
+        PowerUpKind::Magnet => "M",
+        PowerUpKind::DoubleJump => "J",
+    }
+}
+
+// 在指定位置生成一个道具，与金币一样随地面移动
+pub fn spawn_pickup(commands: &mut Commands, kind: PowerUpKind, position: Vec3, lane: Lane, speed: f32) {
+    commands
+        .spawn((
+            Sprite::from_color(pickup_color(kind), Vec2::splat(PICKUP_SIZE)),
+            Transform::from_translation(position),
+            PowerUpPickup(kind),
+            lane,
+            Velocity { x: -speed },
+        ))
+        .with_child((
+            Text2d::new(pickup_letter(kind)),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+            Transform::from_xyz(0.0, 0.0, 0.1),
+        ));
+}
+
+// 慢动作只放慢地面上的物体，道具计时照常进行
+fn tick_powerups(time: Res<Time>, mut query: Query<&mut ActivePowerUps>) {
+    let dt = time.delta_secs();
+    for mut powerups in query.iter_mut() {
+        if powerups.remaining.iter().any(|remaining| *remaining > 0.0) {
+            for remaining in powerups.remaining.iter_mut() {
+                *remaining = (*remaining - dt).max(0.0);
+            }
+        }
+    }
+}
+
+fn collect_powerups(
+    mut commands: Commands,
+    table: Res<PowerUps>,
+    mut collected: EventWriter<events::PowerUpCollected>,
+    mut player_query: Query<(&Player, &Transform, &Lane, &mut ActivePowerUps)>,
+    pickup_query: Query<(Entity, &PowerUpPickup, &Transform, &Lane), Without<Player>>,
+) {
+    for (player, player_transform, lane, mut powerups) in player_query.iter_mut() {
+        if player.crashed {
+            continue;
+        }
+        let player_pos = player_transform.translation;
+        for (entity, pickup, pickup_transform, pickup_lane) in pickup_query.iter() {
+            let pickup_pos = pickup_transform.translation;
+            if pickup_lane == lane
+                && (player_pos.x - pickup_pos.x).abs() < 25.0
+                && (player_pos.y - pickup_pos.y).abs() < 25.0
+            {
+                // 重复吃到同一种道具时重新计时
+                powerups.remaining[pickup.0 as usize] = table.0.duration(pickup.0);
+                collected.write(events::PowerUpCollected);
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+// 磁铁生效时，范围内还没错过的金币直接飞向玩家
+fn attract_coins(
+    time: Res<Time>,
+    table: Res<PowerUps>,
+    player_query: Query<(&Player, &Transform, &Lane, &ActivePowerUps)>,
+    mut coin_query: Query<(&Collectible, &mut Transform, &Lane), Without<Player>>,
+) {
+    let step = MAGNET_SPEED * time.delta_secs();
+    for (player, player_transform, lane, powerups) in player_query.iter() {
+        if player.crashed || !powerups.is_active(PowerUpKind::Magnet) {
+            continue;
+        }
+        let target = player_transform.translation.truncate();
+        for (coin, mut coin_transform, coin_lane) in coin_query.iter_mut() {
+            if coin_lane != lane || coin.missed {
+                continue;
+            }
+            let offset = target - coin_transform.translation.truncate();
+            if offset.length() < table.0.magnet_radius {
+                let movement = offset.clamp_length_max(step);
+                coin_transform.translation += movement.extend(0.0);
+            }
+        }
+    }
+}
+
+// 任意一名玩家处于慢动作时，地面、障碍物和金币按配置的倍率变慢；
+// 重力、跳跃和各种计时不受影响，手感与平时一致
+pub fn apply_slow_mo(table: Res<PowerUps>, query: Query<&ActivePowerUps>, mut slow_mo: ResMut<SlowMo>) {
+    let slow = query.iter().any(|powerups| powerups.is_active(PowerUpKind::SlowMo));
+    let factor = if slow { table.0.slow_factor } else { 1.0 };
+    if slow_mo.0 != factor {
+        slow_mo.0 = factor;
+    }
+}
+
+fn reset_slow_mo(mut slow_mo: ResMut<SlowMo>) {
+    slow_mo.0 = 1.0;
+}
+
+fn update_powerup_text(
+    player_query: Query<(&Lane, &ActivePowerUps)>,
+    mut text_query: Query<(&mut Text2d, &Lane), With<PowerUpText>>,
+) {
+    for (lane, powerups) in player_query.iter() {
+        let label = PowerUpKind::ALL
+            .iter()
+            .filter(|kind| powerups.is_active(**kind))
+            .map(|kind| format!("{} {:.1}s", kind.label(), powerups.remaining[*kind as usize]))
+            .collect::<Vec<_>>()
+            .join("  ");
+        for (mut text, text_lane) in text_query.iter_mut() {
+            if text_lane == lane && text.0 != label {
+                text.0 = label.clone();
+            }
+        }
+    }
+}
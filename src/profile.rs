@@ -0,0 +1,159 @@
+// 玩家档案：跨局保存的个人进度，包括已解锁的成就及解锁日期和生涯统计
+// 保存在存档目录的 profile.txt 中
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io;
+
+use crate::achievement;
+use crate::daily::Date;
+use crate::stats::Stats;
+use crate::storage;
+
+const PROFILE_HEADER: &str = "dino-profile v1";
+const PROFILE_FILE: &str = "profile.txt";
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Profile {
+    unlocked: BTreeMap<String, Date>, // 成就 id 到解锁日期
+    pub stats: Stats,
+}
+
+impl Profile {
+    // 文件不存在或损坏时从空档案开始
+    pub fn load() -> Profile {
+        storage::read_file(PROFILE_FILE)
+            .ok()
+            .and_then(|content| Profile::from_text(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        storage::write_file(PROFILE_FILE, &self.to_text())
+    }
+
+    pub fn unlocked(&self, id: &str) -> Option<Date> {
+        self.unlocked.get(id).copied()
+    }
+
+    pub fn unlocked_count(&self) -> usize {
+        self.unlocked.len()
+    }
+
+    // 首次解锁时返回 true
+    pub fn unlock(&mut self, id: &str, date: Date) -> bool {
+        if self.unlocked.contains_key(id) {
+            return false;
+        }
+        self.unlocked.insert(id.to_string(), date);
+        true
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut content = format!("{}\n", PROFILE_HEADER);
+        for (id, date) in &self.unlocked {
+            let _ = writeln!(content, "achievement {} {}", id, date);
+        }
+        let stats = &self.stats;
+        let _ = writeln!(content, "runs {}", stats.runs);
+        let _ = writeln!(content, "jumps {}", stats.jumps);
+        let _ = writeln!(content, "distance {}", stats.distance);
+        for (cactus, (cleared, deaths)) in stats.cleared.iter().zip(&stats.deaths).enumerate() {
+            let _ = writeln!(content, "cleared {} {}", cactus, cleared);
+            let _ = writeln!(content, "deaths {} {}", cactus, deaths);
+        }
+        let _ = writeln!(content, "longest_run {}", stats.longest_run);
+        let scores: Vec<String> = stats.scores.iter().map(u32::to_string).collect();
+        let _ = writeln!(content, "scores {}", scores.join(" "));
+        content
+    }
+
+    // 忽略已经不存在的成就和无法解析的行
+    pub fn from_text(content: &str) -> io::Result<Profile> {
+        let mut lines = content.lines();
+        if lines.next().map(str::trim) != Some(PROFILE_HEADER) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "玩家档案文件头不匹配"));
+        }
+
+        let mut profile = Profile::default();
+        for line in lines {
+            let (key, value) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+            let stats = &mut profile.stats;
+            match key {
+                "achievement" => {
+                    let Some((id, date)) = value.split_once(' ') else {
+                        continue;
+                    };
+                    if let (Some(achievement), Some(date)) = (achievement::find(id), Date::parse(date)) {
+                        profile.unlocked.insert(achievement.id.to_string(), date);
+                    }
+                }
+                "runs" => stats.runs = value.parse().unwrap_or(0),
+                "jumps" => stats.jumps = value.parse().unwrap_or(0),
+                "distance" => stats.distance = value.parse().unwrap_or(0.0),
+                "longest_run" => stats.longest_run = value.parse().unwrap_or(0.0),
+                "cleared" | "deaths" => {
+                    let counts = if key == "cleared" { &mut stats.cleared } else { &mut stats.deaths };
+                    let Some((cactus, count)) = value.split_once(' ') else {
+                        continue;
+                    };
+                    let total = cactus.parse().ok().and_then(|cactus: usize| counts.get_mut(cactus));
+                    if let (Some(total), Ok(count)) = (total, count.parse()) {
+                        *total = count;
+                    }
+                }
+                "scores" => stats.scores = value.split_whitespace().filter_map(|score| score.parse().ok()).collect(),
+                _ => {}
+            }
+        }
+        Ok(profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::RunSummary;
+
+    #[test]
+    fn text_round_trip() {
+        let mut profile = Profile::default();
+        assert!(profile.unlock("first_cactus", Date::from_days(19_723)));
+        assert!(profile.unlock("night", Date::from_days(19_724)));
+        profile.stats.record(&RunSummary {
+            score: 12,
+            time: 33.5,
+            distance: 9000.0,
+            jumps: 20,
+            cleared: [8, 4],
+            killed_by: Some(1),
+        });
+        profile.stats.record(&RunSummary {
+            score: 3,
+            killed_by: None,
+            ..RunSummary::default()
+        });
+        assert_eq!(Profile::from_text(&profile.to_text()).unwrap(), profile);
+    }
+
+    #[test]
+    fn unlock_keeps_the_first_date() {
+        let mut profile = Profile::default();
+        assert!(profile.unlock("night", Date::from_days(1)));
+        assert!(!profile.unlock("night", Date::from_days(2)));
+        assert_eq!(profile.unlocked("night"), Some(Date::from_days(1)));
+        assert_eq!(profile.unlocked_count(), 1);
+    }
+
+    #[test]
+    fn ignores_unknown_achievements_and_bad_lines() {
+        let profile = Profile::from_text(
+            "dino-profile v1\nachievement retired_goal 2024-01-01\nachievement night not-a-date\nruns x\ncleared 9 3\nscores 4 x 5\n",
+        )
+        .unwrap();
+        assert_eq!(profile.unlocked_count(), 0);
+        assert_eq!(profile.stats.runs, 0);
+        assert_eq!(profile.stats.scores, [4, 5]);
+        assert!(Profile::from_text("dino-profile v2\n").is_err());
+    }
+}
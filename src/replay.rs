@@ -0,0 +1,163 @@
+// 跑酷录像：记录一局的种子、起跳时间和距离曲线，用于幽灵回放
+
+use std::fmt::Write as _;
+use std::io;
+
+use crate::difficulty::Difficulty;
+
+const RUN_HEADER: &str = "dino-run v1";
+
+// 距离采样间隔（秒），回放时在采样点之间线性插值
+pub const SAMPLE_INTERVAL: f32 = 0.25;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunRecording {
+    pub seed: u64,
+    pub difficulty: Difficulty, // 不同难度的障碍物序列不同，只和同难度的录像赛跑
+    pub score: u32,
+    pub duration: f32,
+    pub distance: f32,
+    pub jumps: Vec<f32>,   // 每次起跳时的局内时间
+    pub samples: Vec<f32>, // 每隔 SAMPLE_INTERVAL 秒的累计距离
+}
+
+impl RunRecording {
+    pub fn new(seed: u64, difficulty: Difficulty) -> Self {
+        Self {
+            seed,
+            difficulty,
+            samples: vec![0.0],
+            ..Default::default()
+        }
+    }
+
+    // 推进录像时钟，需要时补充距离采样点
+    pub fn advance(&mut self, dt: f32, distance_delta: f32) {
+        self.duration += dt;
+        self.distance += distance_delta;
+        while self.samples.len() as f32 * SAMPLE_INTERVAL <= self.duration {
+            self.samples.push(self.distance);
+        }
+    }
+
+    pub fn record_jump(&mut self) {
+        self.jumps.push(self.duration);
+    }
+
+    // 回放时某一时刻的累计距离，录像结束后停在最终距离
+    pub fn distance_at(&self, time: f32) -> f32 {
+        if time >= self.duration {
+            return self.distance;
+        }
+        let position = (time / SAMPLE_INTERVAL).max(0.0);
+        let index = position as usize;
+        match (self.samples.get(index), self.samples.get(index + 1)) {
+            (Some(&a), Some(&b)) => a + (b - a) * position.fract(),
+            (Some(&a), None) => {
+                // 最后一个采样点到结束之间
+                let span = self.duration - index as f32 * SAMPLE_INTERVAL;
+                let t = (time - index as f32 * SAMPLE_INTERVAL) / span.max(f32::EPSILON);
+                a + (self.distance - a) * t.clamp(0.0, 1.0)
+            }
+            _ => self.distance,
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut content = format!("{}\n", RUN_HEADER);
+        let _ = writeln!(content, "seed {}", self.seed);
+        let _ = writeln!(content, "difficulty {}", self.difficulty.name());
+        let _ = writeln!(content, "score {}", self.score);
+        let _ = writeln!(content, "duration {}", self.duration);
+        let _ = writeln!(content, "distance {}", self.distance);
+        let _ = writeln!(content, "jumps {}", join(&self.jumps));
+        let _ = writeln!(content, "samples {}", join(&self.samples));
+        content
+    }
+
+    pub fn from_text(content: &str) -> io::Result<RunRecording> {
+        let mut lines = content.lines();
+        if lines.next().map(str::trim) != Some(RUN_HEADER) {
+            return Err(invalid("录像文件头不匹配"));
+        }
+
+        let mut recording = RunRecording::default();
+        for line in lines {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "seed" => recording.seed = value.trim().parse().map_err(invalid)?,
+                // 旧录像没有这一行，按默认难度处理
+                "difficulty" => recording.difficulty = Difficulty::from_name(value.trim()).unwrap_or_default(),
+                "score" => recording.score = value.trim().parse().map_err(invalid)?,
+                "duration" => recording.duration = value.trim().parse().map_err(invalid)?,
+                "distance" => recording.distance = value.trim().parse().map_err(invalid)?,
+                "jumps" => recording.jumps = split(value)?,
+                "samples" => recording.samples = split(value)?,
+                _ => {}
+            }
+        }
+        Ok(recording)
+    }
+}
+
+fn join(values: &[f32]) -> String {
+    values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+fn split(value: &str) -> io::Result<Vec<f32>> {
+    value
+        .split_whitespace()
+        .map(|v| v.parse().map_err(invalid))
+        .collect()
+}
+
+fn invalid(e: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_recording() -> RunRecording {
+        let mut recording = RunRecording::new(42, Difficulty::Hard);
+        // 步长取 0.125 秒，正好落在采样点上，浮点累加没有误差
+        for step in 0..10 {
+            if step % 3 == 0 {
+                recording.record_jump();
+            }
+            recording.advance(0.125, 30.0);
+        }
+        recording.score = 7;
+        recording
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let recording = sample_recording();
+        assert_eq!(RunRecording::from_text(&recording.to_text()).unwrap(), recording);
+    }
+
+    #[test]
+    fn old_recordings_without_difficulty_use_default() {
+        let recording = RunRecording::from_text("dino-run v1\nseed 3\nscore 1\n").unwrap();
+        assert_eq!(recording.seed, 3);
+        assert_eq!(recording.difficulty, Difficulty::default());
+    }
+
+    #[test]
+    fn rejects_wrong_header_and_bad_numbers() {
+        assert!(RunRecording::from_text("dino-run v2\nseed 3\n").is_err());
+        assert!(RunRecording::from_text("dino-run v1\nsamples 0 x\n").is_err());
+    }
+
+    #[test]
+    fn distance_interpolates_between_samples() {
+        let recording = sample_recording();
+        // 匀速前进 240 像素/秒，包括最后一个采样点到结束之间
+        for time in [0.0, 0.3, 0.55, 1.0, 1.2] {
+            assert!((recording.distance_at(time) - time * 240.0).abs() < 1e-3, "{}", time);
+        }
+        assert_eq!(recording.distance_at(5.0), recording.distance);
+    }
+}
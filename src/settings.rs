@@ -0,0 +1,173 @@
+// 玩家设置，保存在存档目录的 settings.txt 中；缺少的条目使用默认值
+
+use std::fmt::Write as _;
+use std::io;
+
+use crate::difficulty::Difficulty;
+use crate::storage;
+use crate::theme::DEFAULT_THEME;
+
+const SETTINGS_HEADER: &str = "dino-settings v1";
+const SETTINGS_FILE: &str = "settings.txt";
+
+// 计分方式：每跳过一个障碍物得 1 分，或按跑过的距离持续得分
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScoreMode {
+    #[default]
+    Obstacles,
+    Distance,
+}
+
+impl ScoreMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            ScoreMode::Obstacles => "obstacles",
+            ScoreMode::Distance => "distance",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<ScoreMode> {
+        match name {
+            "obstacles" => Some(ScoreMode::Obstacles),
+            "distance" => Some(ScoreMode::Distance),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub theme: String,
+    pub reduced_effects: bool, // 减少粒子等视觉特效
+    pub shake_strength: f32,   // 撞车时镜头抖动强度，0 为关闭
+    pub hit_stop_secs: f32,    // 撞车后定格的秒数，0 为关闭
+    pub volume: f32,           // 总音量 0~1
+    pub muted: bool,
+    pub music: bool,           // 是否播放背景音乐
+    pub score_mode: ScoreMode,
+    pub lives_mode: bool,      // 生命模式：撞车只扣一条命，命用完才结束
+    pub difficulty: Difficulty,
+    pub utc_offset: f32,       // 每日挑战按这个时区（相对 UTC 的小时数）换日期
+    pub player_name: String,   // 上次上榜时输入的名字，作为下次的默认值
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: DEFAULT_THEME.to_string(),
+            reduced_effects: false,
+            shake_strength: 1.0,
+            hit_stop_secs: 0.15,
+            volume: 0.8,
+            muted: false,
+            music: true,
+            score_mode: ScoreMode::default(),
+            lives_mode: false,
+            difficulty: Difficulty::default(),
+            utc_offset: 0.0,
+            player_name: "Player".to_string(),
+        }
+    }
+}
+
+impl Settings {
+    // 读取设置，文件不存在或损坏时使用默认设置
+    pub fn load() -> Settings {
+        storage::read_file(SETTINGS_FILE)
+            .ok()
+            .and_then(|content| Settings::from_text(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        storage::write_file(SETTINGS_FILE, &self.to_text())
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut content = format!("{}\n", SETTINGS_HEADER);
+        let _ = writeln!(content, "theme {}", self.theme);
+        let _ = writeln!(content, "reduced_effects {}", self.reduced_effects as u8);
+        let _ = writeln!(content, "shake_strength {}", self.shake_strength);
+        let _ = writeln!(content, "hit_stop {}", self.hit_stop_secs);
+        let _ = writeln!(content, "volume {}", self.volume);
+        let _ = writeln!(content, "muted {}", self.muted as u8);
+        let _ = writeln!(content, "music {}", self.music as u8);
+        let _ = writeln!(content, "score_mode {}", self.score_mode.name());
+        let _ = writeln!(content, "lives {}", self.lives_mode as u8);
+        let _ = writeln!(content, "difficulty {}", self.difficulty.name());
+        let _ = writeln!(content, "utc_offset {}", self.utc_offset);
+        let _ = writeln!(content, "player_name {}", self.player_name);
+        content
+    }
+
+    pub fn from_text(content: &str) -> io::Result<Settings> {
+        let mut lines = content.lines();
+        if lines.next().map(str::trim) != Some(SETTINGS_HEADER) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "设置文件头不匹配"));
+        }
+
+        let mut settings = Settings::default();
+        for line in lines {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let value = value.trim();
+            match key {
+                "theme" if !value.is_empty() => settings.theme = value.to_string(),
+                "reduced_effects" => settings.reduced_effects = value == "1",
+                "shake_strength" => settings.shake_strength = parse_or(value, settings.shake_strength).max(0.0),
+                "hit_stop" => settings.hit_stop_secs = parse_or(value, settings.hit_stop_secs).clamp(0.0, 1.0),
+                "volume" => settings.volume = parse_or(value, settings.volume).clamp(0.0, 1.0),
+                "muted" => settings.muted = value == "1",
+                "music" => settings.music = value == "1",
+                "score_mode" => settings.score_mode = ScoreMode::from_name(value).unwrap_or_default(),
+                "lives" => settings.lives_mode = value == "1",
+                "difficulty" => settings.difficulty = Difficulty::from_name(value).unwrap_or_default(),
+                "player_name" if !value.is_empty() => settings.player_name = value.to_string(),
+                "utc_offset" => settings.utc_offset = parse_or(value, settings.utc_offset).clamp(-12.0, 14.0),
+                _ => {}
+            }
+        }
+        Ok(settings)
+    }
+}
+
+fn parse_or(value: &str, default: f32) -> f32 {
+    value.parse().unwrap_or(default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_round_trip() {
+        let settings = Settings {
+            theme: "classic".to_string(),
+            reduced_effects: true,
+            shake_strength: 0.5,
+            hit_stop_secs: 0.0,
+            volume: 0.25,
+            muted: true,
+            music: false,
+            score_mode: ScoreMode::Distance,
+            lives_mode: true,
+            difficulty: Difficulty::Hard,
+            utc_offset: 5.5,
+            player_name: "Dino Fan".to_string(),
+        };
+        assert_eq!(Settings::from_text(&settings.to_text()).unwrap(), settings);
+    }
+
+    #[test]
+    fn rejects_wrong_header() {
+        assert!(Settings::from_text("dino-settings v2\nvolume 0.5\n").is_err());
+        assert!(Settings::from_text("").is_err());
+    }
+
+    #[test]
+    fn bad_values_fall_back_to_defaults() {
+        let settings = Settings::from_text("dino-settings v1\nvolume loud\nutc_offset local\nhit_stop 9\n").unwrap();
+        assert_eq!(settings.volume, Settings::default().volume);
+        assert_eq!(settings.utc_offset, 0.0);
+        assert_eq!(settings.hit_stop_secs, 1.0);
+    }
+}
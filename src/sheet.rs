@@ -0,0 +1,152 @@
+// 精灵表描述：一张按网格排列的图片，加上若干命名动画片段（帧序号和每帧时长）
+
+use std::collections::HashMap;
+use std::io;
+
+const SHEET_HEADER: &str = "dino-sheet v1";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clip {
+    pub frames: Vec<usize>,  // 精灵表中的帧序号，按行从左到右编号
+    pub frame_duration: f32, // 每帧秒数
+    pub looping: bool,       // 不循环的片段播完后停在最后一帧
+}
+
+impl Clip {
+    // 播放了 time 秒后应显示的帧序号
+    pub fn frame_at(&self, time: f32) -> usize {
+        if self.frames.is_empty() {
+            return 0;
+        }
+        let step = (time.max(0.0) / self.frame_duration.max(f32::EPSILON)) as usize;
+        let index = if self.looping {
+            step % self.frames.len()
+        } else {
+            step.min(self.frames.len() - 1)
+        };
+        self.frames[index]
+    }
+
+    // 不循环的片段是否已经播完
+    pub fn finished(&self, time: f32) -> bool {
+        !self.looping && time >= self.frame_duration * self.frames.len() as f32
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SheetDescriptor {
+    pub image: String, // 相对于描述文件所在目录
+    pub frame_size: (u32, u32),
+    pub columns: u32,
+    pub rows: u32,
+    pub clips: HashMap<String, Clip>,
+}
+
+impl SheetDescriptor {
+    pub fn from_text(content: &str) -> io::Result<SheetDescriptor> {
+        let mut lines = content.lines();
+        if lines.next().map(str::trim) != Some(SHEET_HEADER) {
+            return Err(invalid("精灵表描述文件头不匹配"));
+        }
+
+        let mut sheet = SheetDescriptor {
+            columns: 1,
+            rows: 1,
+            ..Default::default()
+        };
+        for line in lines {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let key = parts.next().unwrap_or_default();
+            match key {
+                "image" => sheet.image = parts.next().unwrap_or_default().to_string(),
+                "frame" => sheet.frame_size = (parse(parts.next())?, parse(parts.next())?),
+                "columns" => sheet.columns = parse(parts.next())?,
+                "rows" => sheet.rows = parse(parts.next())?,
+                // clip <名称> <每帧秒数> <loop|once> <帧序号...>
+                "clip" => {
+                    let name = parts.next().ok_or_else(|| invalid("动画片段缺少名称"))?;
+                    let frame_duration = parse(parts.next())?;
+                    let looping = match parts.next() {
+                        Some("loop") => true,
+                        Some("once") => false,
+                        other => return Err(invalid(format!("未知的播放方式 {:?}", other))),
+                    };
+                    let frames = parts.map(|v| parse(Some(v))).collect::<io::Result<Vec<usize>>>()?;
+                    if frames.iter().any(|&frame| frame >= (sheet.columns * sheet.rows) as usize) {
+                        return Err(invalid(format!("动画片段 {} 的帧序号超出精灵表", name)));
+                    }
+                    sheet.clips.insert(
+                        name.to_string(),
+                        Clip {
+                            frames,
+                            frame_duration,
+                            looping,
+                        },
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        if sheet.image.is_empty() || sheet.frame_size.0 == 0 || sheet.frame_size.1 == 0 {
+            return Err(invalid("精灵表缺少图片或帧尺寸"));
+        }
+        Ok(sheet)
+    }
+}
+
+fn parse<T: std::str::FromStr>(value: Option<&str>) -> io::Result<T>
+where
+    T::Err: ToString,
+{
+    value.ok_or_else(|| invalid("缺少数值"))?.parse().map_err(invalid)
+}
+
+fn invalid(e: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bundled_dino_sheet() {
+        let sheet = SheetDescriptor::from_text(include_str!("../assets/themes/default/dino_sheet.txt")).unwrap();
+        assert!(!sheet.image.is_empty());
+        let frame_count = (sheet.columns * sheet.rows) as usize;
+        for clip in sheet.clips.values() {
+            assert!(clip.frames.iter().all(|&frame| frame < frame_count));
+        }
+    }
+
+    #[test]
+    fn rejects_frames_outside_the_grid_and_missing_image() {
+        let frames = "dino-sheet v1\nimage a.png\nframe 8 8\ncolumns 2\nrows 1\nclip run 0.1 loop 0 2\n";
+        assert!(SheetDescriptor::from_text(frames).is_err());
+        assert!(SheetDescriptor::from_text("dino-sheet v1\nframe 8 8\n").is_err());
+        assert!(SheetDescriptor::from_text("dino-sheet v1\nimage a.png\nframe 8 8\nclip run 0.1 bounce 0\n").is_err());
+    }
+
+    #[test]
+    fn looping_clip_wraps_and_once_clip_holds_last_frame() {
+        let looping = Clip {
+            frames: vec![3, 4, 5],
+            frame_duration: 0.1,
+            looping: true,
+        };
+        assert_eq!(looping.frame_at(0.0), 3);
+        assert_eq!(looping.frame_at(0.15), 4);
+        assert_eq!(looping.frame_at(0.35), 3);
+        assert!(!looping.finished(10.0));
+
+        let once = Clip { looping: false, ..looping };
+        assert_eq!(once.frame_at(0.35), 5);
+        assert!(!once.finished(0.25));
+        assert!(once.finished(0.35));
+    }
+}
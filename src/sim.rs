@@ -0,0 +1,256 @@
+// 无界面的游戏逻辑模拟
+// 与 Bevy 版本共用同一套物理参数和障碍物规则，供训练器在多线程中批量运行
+
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+use crate::difficulty::DifficultyCurve;
+use crate::generator::FairGenerator;
+
+// 游戏常量
+pub const WINDOW_WIDTH: f32 = 800.0;
+pub const GROUND_Y: f32 = -150.0;
+pub const GRAVITY: f32 = -1200.0;
+pub const JUMP_SPEED: f32 = 500.0;
+pub const GAME_SPEED: f32 = 300.0;
+
+// 恐龙与障碍物的位置参数
+pub const PLAYER_X: f32 = -300.0;
+pub const PLAYER_GROUND_Y: f32 = GROUND_Y + 30.0; // 恐龙站在地面上时的中心高度
+pub const JUMP_COOLDOWN: f32 = 0.1;
+pub const OBSTACLE_SPAWN_X: f32 = 500.0;
+pub const DESPAWN_X: f32 = -500.0;
+pub const COLLISION_THRESHOLD: f32 = 25.0;
+
+// 障碍物生成规则
+pub const FIRST_OBSTACLE_DELAY: f32 = 2.0;
+pub const OBSTACLE_PROBABILITY: f64 = 0.85;
+pub const OBSTACLE_INTERVAL: std::ops::Range<f32> = 0.5..1.8;
+pub const CACTUS_CONFIGS: [(f32, f32); 2] = [
+    (25.0, 45.0), // cactus1 - 较小
+    (35.0, 55.0), // cactus2 - 较大
+];
+
+// 每隔这么多分触发一次里程碑（提示音和分数闪烁）
+pub const MILESTONE_POINTS: u32 = 100;
+// 距离计分模式下每跑过这么多像素得 1 分，按 GAME_SPEED 约每秒 10 分
+pub const DISTANCE_PER_POINT: f32 = 30.0;
+
+// 神经网络的传感器数量
+pub const SENSOR_COUNT: usize = 7;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SimObstacle {
+    pub x: f32,
+    pub width: f32,
+    pub height: f32,
+    pub scored: bool,
+}
+
+// 单局游戏的完整状态，按固定步长推进
+pub struct Simulation {
+    rng: StdRng,
+    curve: DifficultyCurve,
+    generator: FairGenerator,
+    pub player_y: f32,
+    pub velocity_y: f32,
+    jump_cooldown: f32,
+    pub obstacles: Vec<SimObstacle>,
+    spawn_timer: f32,
+    pub elapsed: f32,
+    pub score: u32,
+    pub crashed: bool,
+}
+
+impl Simulation {
+    // 使用与常量一致的单段难度，训练器默认使用
+    pub fn new(seed: u64) -> Self {
+        Self::with_curve(seed, DifficultyCurve::default())
+    }
+
+    pub fn with_curve(seed: u64, curve: DifficultyCurve) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            curve,
+            generator: FairGenerator::default(),
+            player_y: PLAYER_GROUND_Y,
+            velocity_y: 0.0,
+            jump_cooldown: 0.0,
+            obstacles: Vec::with_capacity(8),
+            spawn_timer: FIRST_OBSTACLE_DELAY,
+            elapsed: 0.0,
+            score: 0,
+            crashed: false,
+        }
+    }
+
+    pub fn on_ground(&self) -> bool {
+        self.player_y <= PLAYER_GROUND_Y
+    }
+
+    // 当前难度分段的世界速度
+    pub fn speed(&self) -> f32 {
+        self.curve.band(self.score).speed
+    }
+
+    // 推进一帧，顺序与 Bevy 中的系统一致：输入 → 重力 → 移动 → 生成 → 碰撞 → 计分 → 清理
+    pub fn step(&mut self, dt: f32, jump: bool) {
+        if self.crashed {
+            return;
+        }
+        self.elapsed += dt;
+
+        if self.jump_cooldown > 0.0 {
+            self.jump_cooldown -= dt;
+        }
+        if jump && self.on_ground() && self.jump_cooldown <= 0.0 {
+            self.velocity_y = JUMP_SPEED;
+            self.jump_cooldown = JUMP_COOLDOWN;
+        }
+
+        self.velocity_y += GRAVITY * dt;
+        self.player_y += self.velocity_y * dt;
+        if self.player_y <= PLAYER_GROUND_Y {
+            self.player_y = PLAYER_GROUND_Y;
+            self.velocity_y = 0.0;
+        }
+
+        let speed = self.speed();
+        for obstacle in self.obstacles.iter_mut() {
+            obstacle.x -= speed * dt;
+        }
+
+        self.spawn_timer -= dt;
+        if self.spawn_timer <= 0.0 {
+            // 模拟里没有道具，世界速度不会放慢
+            let pattern = self.generator.next(&mut self.rng, &self.curve, self.score, 1.0);
+            if let Some((cactus_index, count)) = pattern.cluster {
+                let (width, height) = CACTUS_CONFIGS[cactus_index];
+                for i in 0..count {
+                    self.obstacles.push(SimObstacle {
+                        x: OBSTACLE_SPAWN_X + i as f32 * width,
+                        width,
+                        height,
+                        scored: false,
+                    });
+                }
+            }
+            self.spawn_timer = pattern.interval;
+        }
+
+        for obstacle in &self.obstacles {
+            let dx = (PLAYER_X - obstacle.x).abs();
+            let dy = (self.player_y - (GROUND_Y + obstacle.height * 0.5)).abs();
+            if dx < COLLISION_THRESHOLD && dy < COLLISION_THRESHOLD {
+                self.crashed = true;
+                return;
+            }
+        }
+
+        for obstacle in self.obstacles.iter_mut() {
+            if !obstacle.scored && obstacle.x < PLAYER_X {
+                obstacle.scored = true;
+                self.score += 1;
+            }
+        }
+
+        self.obstacles.retain(|obstacle| obstacle.x >= DESPAWN_X);
+    }
+
+    pub fn sensors(&self) -> [f32; SENSOR_COUNT] {
+        sensors(
+            self.player_y,
+            self.velocity_y,
+            self.speed(),
+            self.obstacles.iter().map(|o| (o.x, o.width, o.height)),
+        )
+    }
+}
+
+// 根据世界状态计算神经网络输入，游戏内的 AI 玩家也使用同一个函数
+// obstacles 为 (x, 宽, 高)，顺序不限
+pub fn sensors(
+    player_y: f32,
+    velocity_y: f32,
+    speed: f32,
+    obstacles: impl Iterator<Item = (f32, f32, f32)>,
+) -> [f32; SENSOR_COUNT] {
+    // 只关注还没越过恐龙的最近两个障碍物
+    let mut ahead: Vec<(f32, f32, f32)> = obstacles
+        .filter(|(x, width, _)| x + width * 0.5 > PLAYER_X - COLLISION_THRESHOLD)
+        .collect();
+    ahead.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let (next_dx, next_width, next_height) = match ahead.first() {
+        Some(&(x, width, height)) => ((x - PLAYER_X) / WINDOW_WIDTH, width / 50.0, height / 60.0),
+        None => (1.0, 0.0, 0.0),
+    };
+    let second_dx = ahead
+        .get(1)
+        .map_or(1.0, |&(x, _, _)| (x - PLAYER_X) / WINDOW_WIDTH);
+
+    [
+        next_dx,
+        next_width,
+        next_height,
+        second_dx,
+        (player_y - PLAYER_GROUND_Y) / 100.0,
+        velocity_y / JUMP_SPEED,
+        speed / GAME_SPEED,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::neuro::SIM_DT;
+
+    // 按固定节奏起跳跑一段时间，返回可以比较的状态；撞车后清掉标记继续跑，覆盖更多障碍物
+    fn run(seed: u64) -> (u32, f32, Vec<(f32, f32, f32)>) {
+        let mut sim = Simulation::new(seed);
+        for frame in 0..3000 {
+            sim.step(SIM_DT, frame % 50 == 0);
+            sim.crashed = false;
+        }
+        let obstacles = sim.obstacles.iter().map(|o| (o.x, o.width, o.height)).collect();
+        (sim.score, sim.elapsed, obstacles)
+    }
+
+    #[test]
+    fn same_seed_gives_same_result() {
+        for seed in [0, 1, 42] {
+            assert_eq!(run(seed), run(seed));
+        }
+    }
+
+    #[test]
+    fn different_seeds_give_different_obstacles() {
+        // 记录前 10 个障碍物出现的时刻和高度
+        let spawned = |seed| {
+            let mut sim = Simulation::new(seed);
+            let mut spawned = Vec::new();
+            while spawned.len() < 10 {
+                let before = sim.obstacles.len();
+                sim.step(SIM_DT, false);
+                sim.crashed = false;
+                if sim.obstacles.len() > before {
+                    spawned.push((sim.elapsed, sim.obstacles.last().unwrap().height));
+                }
+            }
+            spawned
+        };
+        assert_ne!(spawned(1), spawned(2));
+    }
+
+    #[test]
+    fn crashed_simulation_stops() {
+        let mut sim = Simulation::new(0);
+        while !sim.crashed {
+            sim.step(SIM_DT, false);
+        }
+        let elapsed = sim.elapsed;
+        sim.step(SIM_DT, true);
+        assert_eq!(sim.elapsed, elapsed);
+        assert!(sim.on_ground());
+    }
+}
@@ -0,0 +1,139 @@
+// 主题切换：当前主题决定贴图、昼夜配色、背景图层颜色和粒子效果
+// 主题来自设置文件或 --theme 参数，菜单和游戏结束画面按 T 可以轮换主题并保存到设置
+
+use std::path::PathBuf;
+
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use dino_game::settings::Settings;
+use dino_game::theme::{self, Rgb, Theme};
+
+use crate::daynight::{self, DayNightConfig, Palette};
+use crate::parallax::{ParallaxConfig, ParallaxShape};
+use crate::particles::{BurstKind, ParticleSettings};
+use crate::GameState;
+
+#[derive(Resource)]
+pub struct ActiveTheme(pub Theme);
+
+#[derive(Resource)]
+pub struct UserSettings(pub Settings);
+
+pub struct SkinPlugin;
+
+impl Plugin for SkinPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                cycle_theme.run_if(
+                    in_state(GameState::Menu)
+                        .or(in_state(GameState::GameOver))
+                        .and(crate::menu::main_page)
+                        .and(not(crate::leaderboards::typing)),
+                ),
+                apply_theme
+                    .run_if(resource_changed::<ActiveTheme>)
+                    .before(daynight::blend_palette),
+            )
+                .chain(),
+        );
+    }
+}
+
+// Bevy 资源目录，主题清单和图片都从这里读取
+pub fn assets_root() -> PathBuf {
+    FileAssetReader::get_base_path().join("assets")
+}
+
+// 加载主题，把退回默认主题或默认精灵表的原因写进日志
+pub fn load_theme(id: &str) -> Theme {
+    let (theme, errors) = theme::load_theme(&assets_root(), id);
+    for e in errors {
+        warn!("{}", e);
+    }
+    theme
+}
+
+pub fn to_color([r, g, b]: Rgb) -> Color {
+    Color::srgb(r, g, b)
+}
+
+// 主题里的一套配色，缺少的颜色保留原值
+fn palette(theme: &Theme, prefix: &str, fallback: Palette) -> Palette {
+    let color = |key: &str, fallback: Color| {
+        theme.color(&format!("{}.{}", prefix, key)).map_or(fallback, to_color)
+    };
+    Palette {
+        sky: color("sky", fallback.sky),
+        ground: color("ground", fallback.ground),
+        sprite: color("sprite", fallback.sprite),
+        hud_text: color("hud_text", fallback.hud_text),
+        background: color("background", fallback.background),
+    }
+}
+
+// 主题变化时更新昼夜配色、背景颜色和粒子参数；贴图由 load_assets 重新加载
+fn apply_theme(
+    active: Res<ActiveTheme>,
+    mut day_night: ResMut<DayNightConfig>,
+    mut parallax: ResMut<ParallaxConfig>,
+    mut particles: ResMut<ParticleSettings>,
+) {
+    let theme = &active.0;
+    let defaults = DayNightConfig::default();
+    day_night.day = palette(theme, "day", defaults.day);
+    day_night.night = palette(theme, "night", defaults.night);
+
+    let defaults = ParallaxConfig::default();
+    for (layer, default_layer) in parallax.layers.iter_mut().zip(&defaults.layers) {
+        let key = match layer.shape {
+            ParallaxShape::Hill => "hill",
+            ParallaxShape::Cloud => "cloud",
+            ParallaxShape::Star => "star",
+            ParallaxShape::Moon => "moon",
+        };
+        layer.color = theme.color(key).map_or(default_layer.color, to_color);
+    }
+
+    *particles = ParticleSettings::default();
+    for (kind, prefix) in [(BurstKind::Dust, "dust"), (BurstKind::Puff, "puff"), (BurstKind::Debris, "debris")] {
+        let style = particles.style_mut(kind);
+        let key = |name: &str| format!("{}.{}", prefix, name);
+        if let Some(color) = theme.color(&key("color")) {
+            style.color = to_color(color);
+        }
+        if let Some(count) = theme.value(&key("count")) {
+            style.count = count.max(0.0) as u32;
+        }
+        if let Some(lifetime) = theme.value(&key("lifetime")) {
+            style.lifetime = lifetime;
+        }
+        if let Some(size) = theme.value(&key("size")) {
+            style.size = size;
+        }
+    }
+}
+
+// 轮换到下一个主题
+fn cycle_theme(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut active: ResMut<ActiveTheme>,
+    mut settings: ResMut<UserSettings>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyT) {
+        return;
+    }
+
+    let root = assets_root();
+    let themes = theme::list_themes(&root);
+    let current = themes.iter().position(|id| *id == active.0.id).unwrap_or(0);
+    let next = &themes[(current + 1) % themes.len()];
+
+    active.0 = load_theme(next);
+    settings.0.theme = active.0.id.clone();
+    if let Err(e) = settings.0.save() {
+        warn!("保存设置失败: {}", e);
+    }
+    info!("切换到主题: {}", active.0.name);
+}
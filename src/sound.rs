@@ -0,0 +1,259 @@
+// 音效和背景音乐：起跳、金币、道具、每 100 分的里程碑和撞车音效，以及可选的循环音乐
+// 声音都由 synth 模块在启动时合成；没有音频设备时声音永远不会开始播放，
+// 发现这种情况后就不再生成声音实体，游戏照常静音运行（例如在无头 CI 中）
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use bevy::audio::{AddAudioSource, Decodable, Source, Volume};
+use bevy::prelude::*;
+use dino_game::settings::Settings;
+use dino_game::synth::{self, SAMPLE_RATE};
+
+use crate::events::{CoinCollected, Crashed, Jumped, MilestoneReached, PowerUpCollected};
+use crate::skin::UserSettings;
+
+// 背景音乐相对音效的音量
+const MUSIC_VOLUME: f32 = 0.4;
+
+// 声音实体超过这么久还没开始播放，说明没有音频设备
+const START_TIMEOUT_SECS: f32 = 1.0;
+
+// 合成好的单声道采样
+#[derive(Asset, TypePath, Clone)]
+pub struct Sound {
+    samples: Arc<[f32]>,
+}
+
+pub struct SoundDecoder {
+    samples: Arc<[f32]>,
+    position: usize,
+}
+
+impl Iterator for SoundDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.samples.get(self.position).copied();
+        self.position += 1;
+        sample
+    }
+}
+
+impl Source for SoundDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some(self.samples.len().saturating_sub(self.position))
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(self.samples.len() as f32 / SAMPLE_RATE as f32))
+    }
+}
+
+impl Decodable for Sound {
+    type DecoderItem = f32;
+    type Decoder = SoundDecoder;
+
+    fn decoder(&self) -> SoundDecoder {
+        SoundDecoder {
+            samples: self.samples.clone(),
+            position: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sfx {
+    Jump,
+    Coin,
+    PowerUp,
+    Milestone,
+    Death,
+}
+
+#[derive(Event)]
+pub struct PlaySfx(pub Sfx);
+
+#[derive(Resource)]
+struct Sounds {
+    jump: Handle<Sound>,
+    coin: Handle<Sound>,
+    powerup: Handle<Sound>,
+    milestone: Handle<Sound>,
+    death: Handle<Sound>,
+    music: Handle<Sound>,
+}
+
+// 所有声音实体都带有该组件，用来检测播放是否真的开始了
+#[derive(Component)]
+struct Voice {
+    waiting: f32,
+}
+
+#[derive(Component)]
+struct Music;
+
+// 是否有可用的音频设备；检测到没有后关闭所有声音
+#[derive(Resource)]
+struct AudioAvailable(bool);
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_source::<Sound>()
+            .add_event::<PlaySfx>()
+            .insert_resource(AudioAvailable(true))
+            .add_systems(Startup, create_sounds)
+            .add_systems(
+                Update,
+                (
+                    queue_gameplay_sounds,
+                    queue_death_sound,
+                    play_sfx,
+                    update_music.run_if(resource_changed::<UserSettings>.or(resource_changed::<AudioAvailable>)),
+                    detect_missing_device,
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn create_sounds(mut commands: Commands, mut sounds: ResMut<Assets<Sound>>) {
+    let mut add = |samples: Vec<f32>| sounds.add(Sound { samples: samples.into() });
+    commands.insert_resource(Sounds {
+        jump: add(synth::jump_sound()),
+        coin: add(synth::coin_sound()),
+        powerup: add(synth::powerup_sound()),
+        milestone: add(synth::milestone_sound()),
+        death: add(synth::death_sound()),
+        music: add(synth::music_loop()),
+    });
+}
+
+// 静音时音量为 0
+fn effective_volume(settings: &Settings) -> f32 {
+    if settings.muted {
+        0.0
+    } else {
+        settings.volume
+    }
+}
+
+// 起跳、金币和道具音效；任一玩家的分数跨过里程碑时播放一次提示音
+fn queue_gameplay_sounds(
+    mut jumped: EventReader<Jumped>,
+    mut coins: EventReader<CoinCollected>,
+    mut powerups: EventReader<PowerUpCollected>,
+    mut milestones: EventReader<MilestoneReached>,
+    mut sfx: EventWriter<PlaySfx>,
+) {
+    for _ in jumped.read() {
+        sfx.write(PlaySfx(Sfx::Jump));
+    }
+    for _ in coins.read() {
+        sfx.write(PlaySfx(Sfx::Coin));
+    }
+    for _ in powerups.read() {
+        sfx.write(PlaySfx(Sfx::PowerUp));
+    }
+    if milestones.read().count() > 0 {
+        sfx.write(PlaySfx(Sfx::Milestone));
+    }
+}
+
+// 同一帧里两名玩家一起撞车时只播放一次
+fn queue_death_sound(mut crashes: EventReader<Crashed>, mut sfx: EventWriter<PlaySfx>) {
+    if crashes.read().count() > 0 {
+        sfx.write(PlaySfx(Sfx::Death));
+    }
+}
+
+fn play_sfx(
+    mut commands: Commands,
+    mut events: EventReader<PlaySfx>,
+    sounds: Res<Sounds>,
+    settings: Res<UserSettings>,
+    available: Res<AudioAvailable>,
+) {
+    let volume = effective_volume(&settings.0);
+    for PlaySfx(sfx) in events.read() {
+        if !available.0 || volume <= 0.0 {
+            continue;
+        }
+        let handle = match sfx {
+            Sfx::Jump => &sounds.jump,
+            Sfx::Coin => &sounds.coin,
+            Sfx::PowerUp => &sounds.powerup,
+            Sfx::Milestone => &sounds.milestone,
+            Sfx::Death => &sounds.death,
+        };
+        commands.spawn((
+            AudioPlayer(handle.clone()),
+            PlaybackSettings::DESPAWN.with_volume(Volume::Linear(volume)),
+            Voice { waiting: 0.0 },
+        ));
+    }
+}
+
+// 根据设置开关背景音乐并调整音量
+fn update_music(
+    mut commands: Commands,
+    sounds: Res<Sounds>,
+    settings: Res<UserSettings>,
+    available: Res<AudioAvailable>,
+    mut music_query: Query<(Entity, Option<&mut AudioSink>), With<Music>>,
+) {
+    let volume = effective_volume(&settings.0) * MUSIC_VOLUME;
+    let enabled = available.0 && settings.0.music && volume > 0.0;
+
+    if !enabled {
+        for (entity, _) in music_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    if music_query.is_empty() {
+        commands.spawn((
+            AudioPlayer(sounds.music.clone()),
+            PlaybackSettings::LOOP.with_volume(Volume::Linear(volume)),
+            Music,
+            Voice { waiting: 0.0 },
+        ));
+    }
+    for (_, sink) in music_query.iter_mut() {
+        if let Some(mut sink) = sink {
+            sink.set_volume(Volume::Linear(volume));
+        }
+    }
+}
+
+fn detect_missing_device(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut available: ResMut<AudioAvailable>,
+    mut voice_query: Query<(Entity, &mut Voice, Has<AudioSink>)>,
+) {
+    for (entity, mut voice, playing) in voice_query.iter_mut() {
+        if playing {
+            continue;
+        }
+        voice.waiting += time.delta_secs();
+        if voice.waiting > START_TIMEOUT_SECS {
+            commands.entity(entity).despawn();
+            if available.0 {
+                warn!("没有可用的音频设备，游戏将静音运行");
+                available.0 = false;
+            }
+        }
+    }
+}
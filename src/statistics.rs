@@ -0,0 +1,200 @@
+// 生涯统计：汇总每个单人局的跳跃、距离、跳过和撞上的障碍物，局末并入玩家档案
+// 菜单里按 S 打开统计页面，右侧用柱状图显示分数分布
+
+use bevy::prelude::*;
+use dino_game::sim::DISTANCE_PER_POINT;
+use dino_game::stats::{RunSummary, Stats, CACTUS_NAMES};
+
+use crate::achievements::{solo_run, PlayerProfile, PLAYER};
+use crate::daynight::{self, CurrentPalette};
+use crate::events::{Crashed, Jumped, ObstacleCleared};
+use crate::menu::MenuPage;
+use crate::{GameScore, GameState, Lane};
+
+// 分数分布柱状图的段数和尺寸
+const BUCKETS: usize = 8;
+const BAR_SLOT: f32 = 40.0;
+const BAR_WIDTH: f32 = 28.0;
+const BAR_MAX_HEIGHT: f32 = 180.0;
+const CHART_LEFT: f32 = 60.0;
+const CHART_BASELINE: f32 = -110.0;
+
+// 本局的统计，撞车丢掉最后一条命后停止计时
+#[derive(Resource, Default)]
+struct CurrentRun {
+    summary: RunSummary,
+    finished: bool,
+}
+
+// 统计页面的根实体，文字和柱子都是它的子实体
+#[derive(Component)]
+struct StatsScreen;
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CurrentRun>()
+            .add_systems(OnEnter(GameState::Playing), reset_run)
+            .add_systems(
+                Update,
+                track_run.after(crate::update_score).run_if(in_state(GameState::Playing).and(solo_run)),
+            )
+            .add_systems(OnEnter(GameState::GameOver), record_run.run_if(solo_run))
+            .add_systems(
+                Update,
+                (toggle_stats, draw_stats).chain().run_if(in_state(GameState::Menu)),
+            )
+            .add_systems(OnExit(GameState::Menu), despawn_stats);
+    }
+}
+
+fn reset_run(mut run: ResMut<CurrentRun>) {
+    *run = CurrentRun::default();
+}
+
+fn track_run(
+    time: Res<Time>,
+    mut jumped: EventReader<Jumped>,
+    mut cleared: EventReader<ObstacleCleared>,
+    mut crashes: EventReader<Crashed>,
+    mut run: ResMut<CurrentRun>,
+) {
+    if run.finished {
+        return;
+    }
+    let run = &mut *run;
+    run.summary.time += time.delta_secs();
+    run.summary.jumps += jumped.read().filter(|event| event.lane == PLAYER).count() as u32;
+    for event in cleared.read().filter(|event| event.lane == PLAYER) {
+        run.summary.cleared[event.cactus] += 1;
+    }
+    if let Some(crash) = crashes.read().find(|crash| crash.lane == PLAYER && crash.lives_left == 0) {
+        run.summary.killed_by = Some(crash.cactus);
+        run.finished = true;
+    }
+}
+
+// 局末并入生涯统计；冲过终点线的关卡局没有撞车记录
+fn record_run(
+    mut run: ResMut<CurrentRun>,
+    mut profile: ResMut<PlayerProfile>,
+    score_query: Query<(&GameScore, &Lane)>,
+) {
+    let Some((score, _)) = score_query.iter().find(|(_, lane)| **lane == PLAYER) else {
+        return;
+    };
+    run.summary.score = score.value;
+    run.summary.distance = score.distance;
+    profile.0.stats.record(&run.summary);
+    if let Err(e) = profile.0.save() {
+        warn!("保存玩家档案失败: {}", e);
+    }
+}
+
+// 按 S 打开统计页面，再按 S 返回主菜单
+fn toggle_stats(keyboard_input: Res<ButtonInput<KeyCode>>, mut page: ResMut<MenuPage>) {
+    if keyboard_input.just_pressed(KeyCode::KeyS) {
+        *page = if *page == MenuPage::Stats { MenuPage::Main } else { MenuPage::Stats };
+    }
+}
+
+// 菜单里统计不会变化，打开页面时生成一次，离开时整体删除
+fn draw_stats(
+    mut commands: Commands,
+    page: Res<MenuPage>,
+    profile: Res<PlayerProfile>,
+    palette: Res<CurrentPalette>,
+    query: Query<Entity, With<StatsScreen>>,
+) {
+    if *page != MenuPage::Stats {
+        for entity in query.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+    if !query.is_empty() {
+        return;
+    }
+
+    let stats = &profile.0.stats;
+    let color = palette.0.hud_text;
+    commands
+        .spawn((StatsScreen, Transform::default(), Visibility::default()))
+        .with_children(|screen| {
+            screen.spawn((
+                daynight::HudText,
+                Text2d::new(summary_text(stats)),
+                Transform::from_xyz(-190.0, 20.0, 1.0),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+            ));
+
+            let chart_center = CHART_LEFT + BAR_SLOT * BUCKETS as f32 * 0.5;
+            let title = if stats.scores.is_empty() { "Score distribution\n(no runs yet)" } else { "Score distribution" };
+            screen.spawn((
+                daynight::HudText,
+                Text2d::new(title),
+                Transform::from_xyz(chart_center, CHART_BASELINE + BAR_MAX_HEIGHT + 40.0, 1.0),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+            ));
+            if stats.scores.is_empty() {
+                return;
+            }
+
+            let histogram = stats.histogram(BUCKETS);
+            let most = histogram.iter().map(|&(_, count)| count).max().unwrap_or(1).max(1);
+            for (index, &(from, count)) in histogram.iter().enumerate() {
+                let x = CHART_LEFT + BAR_SLOT * (index as f32 + 0.5);
+                let height = (count as f32 / most as f32 * BAR_MAX_HEIGHT).max(1.0);
+                screen.spawn((
+                    Sprite::from_color(color, Vec2::new(BAR_WIDTH, height)),
+                    Transform::from_xyz(x, CHART_BASELINE + height * 0.5, 1.0),
+                ));
+                // 柱子上方是局数，下方是这一段的起始分数
+                for (label, y) in [(count, CHART_BASELINE + height + 10.0), (from, CHART_BASELINE - 12.0)] {
+                    screen.spawn((
+                        daynight::HudText,
+                        Text2d::new(label.to_string()),
+                        Transform::from_xyz(x, y, 1.0),
+                        TextFont {
+                            font_size: 12.0,
+                            ..default()
+                        },
+                    ));
+                }
+            }
+        });
+}
+
+// 距离与 HUD 按距离计分时的单位一致，每 DISTANCE_PER_POINT 像素算 1 分
+fn summary_text(stats: &Stats) -> String {
+    let per_type = |counts: &[u32]| {
+        CACTUS_NAMES.iter().zip(counts).map(|(name, count)| format!("{} {}", name, count)).collect::<Vec<_>>().join(", ")
+    };
+    let score = |value: Option<f32>| value.map_or(String::from("-"), |value| format!("{:.1}", value));
+    let longest = stats.longest_run as u32;
+    format!(
+        "LIFETIME STATS\n\nRuns: {}\nJumps: {}\nDistance: {:.0} points\nCleared: {}\nDeaths: {}\nAverage score: {}\nMedian score: {}\nLongest run: {}:{:02}\n\nS/ESC: back",
+        stats.runs,
+        stats.jumps,
+        stats.distance / DISTANCE_PER_POINT,
+        per_type(&stats.cleared),
+        per_type(&stats.deaths),
+        score(stats.average_score()),
+        score(stats.median_score()),
+        longest / 60,
+        longest % 60,
+    )
+}
+
+fn despawn_stats(mut commands: Commands, query: Query<Entity, With<StatsScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
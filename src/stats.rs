@@ -0,0 +1,141 @@
+// 生涯统计：所有单人局累计的跑局、跳跃、距离、障碍物和分数，随玩家档案保存
+
+use crate::sim::CACTUS_CONFIGS;
+
+pub const CACTUS_NAMES: [&str; CACTUS_CONFIGS.len()] = ["small", "large"];
+
+// 一局结束时汇总的数据
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunSummary {
+    pub score: u32,
+    pub time: f32,     // 秒
+    pub distance: f32, // 像素
+    pub jumps: u32,
+    pub cleared: [u32; CACTUS_CONFIGS.len()],
+    pub killed_by: Option<usize>, // 撞死恐龙的仙人掌；冲过终点线时为 None
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    pub runs: u32,
+    pub jumps: u32,
+    pub distance: f32,
+    pub cleared: [u32; CACTUS_CONFIGS.len()],
+    pub deaths: [u32; CACTUS_CONFIGS.len()],
+    pub longest_run: f32,
+    pub scores: Vec<u32>, // 每局的分数，按时间顺序
+}
+
+impl Stats {
+    pub fn record(&mut self, run: &RunSummary) {
+        self.runs += 1;
+        self.jumps += run.jumps;
+        self.distance += run.distance;
+        for (total, cleared) in self.cleared.iter_mut().zip(run.cleared) {
+            *total += cleared;
+        }
+        if let Some(deaths) = run.killed_by.and_then(|cactus| self.deaths.get_mut(cactus)) {
+            *deaths += 1;
+        }
+        self.longest_run = self.longest_run.max(run.time);
+        self.scores.push(run.score);
+    }
+
+    pub fn average_score(&self) -> Option<f32> {
+        if self.scores.is_empty() {
+            return None;
+        }
+        Some(self.scores.iter().map(|&score| score as f32).sum::<f32>() / self.scores.len() as f32)
+    }
+
+    // 偶数局时取中间两局的平均；先转成 u64 再相加，分数很大时也不会溢出
+    pub fn median_score(&self) -> Option<f32> {
+        let mut sorted = self.scores.clone();
+        sorted.sort_unstable();
+        let middle = sorted.len() / 2;
+        match sorted.len() {
+            0 => None,
+            len if len % 2 == 1 => Some(sorted[middle] as f32),
+            _ => Some((sorted[middle - 1] as u64 + sorted[middle] as u64) as f32 * 0.5),
+        }
+    }
+
+    // 分数分布：把 0 到最高分等分成 buckets 段，返回每段的起始分数和局数
+    pub fn histogram(&self, buckets: usize) -> Vec<(u32, u32)> {
+        let max = self.scores.iter().copied().max().unwrap_or(0);
+        let width = (max / buckets as u32 + 1).max(1);
+        let mut counts = vec![0; buckets];
+        for &score in &self.scores {
+            counts[((score / width) as usize).min(buckets - 1)] += 1;
+        }
+        counts.into_iter().enumerate().map(|(index, count)| (index as u32 * width, count)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_scores(scores: &[u32]) -> Stats {
+        Stats {
+            scores: scores.to_vec(),
+            ..Stats::default()
+        }
+    }
+
+    #[test]
+    fn median_handles_empty_single_odd_and_even() {
+        assert_eq!(with_scores(&[]).median_score(), None);
+        assert_eq!(with_scores(&[7]).median_score(), Some(7.0));
+        assert_eq!(with_scores(&[9, 1, 5]).median_score(), Some(5.0));
+        assert_eq!(with_scores(&[8, 1, 4, 2]).median_score(), Some(3.0));
+        // 中间两局相加超出 u32 也不会溢出
+        assert_eq!(with_scores(&[u32::MAX, u32::MAX]).median_score(), Some(u32::MAX as f32));
+    }
+
+    #[test]
+    fn average_of_no_runs_is_none() {
+        assert_eq!(with_scores(&[]).average_score(), None);
+        assert_eq!(with_scores(&[1, 2]).average_score(), Some(1.5));
+    }
+
+    #[test]
+    fn histogram_edge_cases() {
+        // 没有分数时每段都是 0 局
+        assert_eq!(with_scores(&[]).histogram(4), [(0, 0), (1, 0), (2, 0), (3, 0)]);
+        // 只有一局 0 分时落在第一段
+        assert_eq!(with_scores(&[0]).histogram(2), [(0, 1), (1, 0)]);
+        // 最高分落在最后一段，各段局数之和等于局数
+        let histogram = with_scores(&[0, 3, 7, 8, 8]).histogram(4);
+        assert_eq!(histogram, [(0, 1), (3, 1), (6, 3), (9, 0)]);
+        assert_eq!(histogram.iter().map(|&(_, count)| count).sum::<u32>(), 5);
+    }
+
+    #[test]
+    fn record_accumulates_runs() {
+        let mut stats = Stats::default();
+        stats.record(&RunSummary {
+            score: 4,
+            time: 10.0,
+            distance: 300.0,
+            jumps: 5,
+            cleared: [3, 1],
+            killed_by: Some(0),
+        });
+        stats.record(&RunSummary {
+            score: 6,
+            time: 20.0,
+            distance: 500.0,
+            jumps: 7,
+            cleared: [2, 2],
+            killed_by: None,
+        });
+        assert_eq!(stats.runs, 2);
+        assert_eq!(stats.jumps, 12);
+        assert_eq!(stats.distance, 800.0);
+        assert_eq!(stats.cleared, [5, 3]);
+        assert_eq!(stats.deaths, [1, 0]);
+        assert_eq!(stats.longest_run, 20.0);
+        assert_eq!(stats.scores, [4, 6]);
+    }
+}
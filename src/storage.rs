@@ -0,0 +1,19 @@
+// 本地存档：所有需要持久化的文件都放在同一个目录下
+
+use std::io;
+use std::path::PathBuf;
+
+pub const SAVE_DIR: &str = "saves";
+
+pub fn save_path(file_name: &str) -> PathBuf {
+    PathBuf::from(SAVE_DIR).join(file_name)
+}
+
+pub fn read_file(file_name: &str) -> io::Result<String> {
+    std::fs::read_to_string(save_path(file_name))
+}
+
+pub fn write_file(file_name: &str, content: &str) -> io::Result<()> {
+    std::fs::create_dir_all(SAVE_DIR)?;
+    std::fs::write(save_path(file_name), content)
+}
@@ -0,0 +1,165 @@
+// 简单的芯片音色合成：用方波、三角波和噪声拼出音效和背景音乐
+// 音效在启动时生成为单声道采样，不需要音频文件
+
+pub const SAMPLE_RATE: u32 = 44100;
+
+// 每个音符开头和结尾的淡入淡出时长，避免爆音
+const FADE_SECS: f32 = 0.005;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wave {
+    Square,
+    Triangle,
+    Noise,
+}
+
+// 一个音符，频率在持续时间内从 start_freq 线性滑到 end_freq；频率为 0 表示休止
+#[derive(Debug, Clone, Copy)]
+pub struct Note {
+    pub wave: Wave,
+    pub start_freq: f32,
+    pub end_freq: f32,
+    pub duration: f32,
+    pub volume: f32,
+}
+
+impl Note {
+    pub fn new(wave: Wave, freq: f32, duration: f32, volume: f32) -> Self {
+        Self {
+            wave,
+            start_freq: freq,
+            end_freq: freq,
+            duration,
+            volume,
+        }
+    }
+
+    pub fn slide(wave: Wave, start_freq: f32, end_freq: f32, duration: f32, volume: f32) -> Self {
+        Self {
+            wave,
+            start_freq,
+            end_freq,
+            duration,
+            volume,
+        }
+    }
+}
+
+// 依次演奏一串音符
+pub fn render(notes: &[Note]) -> Vec<f32> {
+    let mut samples = Vec::new();
+    let mut phase = 0.0f32;
+    let mut noise = 0x1234_5678u32;
+    for note in notes {
+        let count = (note.duration * SAMPLE_RATE as f32) as usize;
+        let fade = ((FADE_SECS * SAMPLE_RATE as f32) as usize).min(count / 2).max(1);
+        for i in 0..count {
+            let t = i as f32 / count as f32;
+            let freq = note.start_freq + (note.end_freq - note.start_freq) * t;
+            if freq <= 0.0 {
+                samples.push(0.0);
+                continue;
+            }
+            phase = (phase + freq / SAMPLE_RATE as f32).fract();
+            let value = match note.wave {
+                Wave::Square => {
+                    if phase < 0.5 {
+                        1.0
+                    } else {
+                        -1.0
+                    }
+                }
+                Wave::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+                Wave::Noise => {
+                    // 线性同余生成器，结果可复现
+                    noise = noise.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                    (noise >> 8) as f32 / (1u32 << 23) as f32 - 1.0
+                }
+            };
+            let envelope = (i.min(count - 1 - i) as f32 / fade as f32).min(1.0);
+            samples.push(value * note.volume * envelope);
+        }
+    }
+    samples
+}
+
+// 把 other 叠加到 base 上，长度取两者较长的
+pub fn mix(mut base: Vec<f32>, other: &[f32]) -> Vec<f32> {
+    if other.len() > base.len() {
+        base.resize(other.len(), 0.0);
+    }
+    for (a, b) in base.iter_mut().zip(other) {
+        *a = (*a + b).clamp(-1.0, 1.0);
+    }
+    base
+}
+
+// 起跳：短促的上滑音
+pub fn jump_sound() -> Vec<f32> {
+    render(&[Note::slide(Wave::Square, 440.0, 880.0, 0.09, 0.25)])
+}
+
+// 吃到道具：快速上行的琶音
+pub fn powerup_sound() -> Vec<f32> {
+    render(&[
+        Note::new(Wave::Square, 523.3, 0.05, 0.18),
+        Note::new(Wave::Square, 659.3, 0.05, 0.18),
+        Note::new(Wave::Square, 784.0, 0.05, 0.18),
+        Note::new(Wave::Square, 1046.5, 0.1, 0.18),
+    ])
+}
+
+// 里程碑：和 Chrome 原版一样的两声高音
+pub fn milestone_sound() -> Vec<f32> {
+    render(&[
+        Note::new(Wave::Square, 1046.5, 0.08, 0.2),
+        Note::new(Wave::Square, 0.0, 0.02, 0.0),
+        Note::new(Wave::Square, 1568.0, 0.14, 0.2),
+    ])
+}
+
+// 吃到金币：快速的两声高音
+pub fn coin_sound() -> Vec<f32> {
+    render(&[
+        Note::new(Wave::Square, 987.8, 0.05, 0.18),
+        Note::new(Wave::Square, 1318.5, 0.12, 0.18),
+    ])
+}
+
+// 撞车：一声噪声接一段下滑的低音
+pub fn death_sound() -> Vec<f32> {
+    mix(
+        render(&[Note::new(Wave::Noise, 1.0, 0.12, 0.35)]),
+        &render(&[Note::slide(Wave::Square, 220.0, 55.0, 0.35, 0.25)]),
+    )
+}
+
+// 背景音乐：四小节循环，三角波低音加方波旋律
+pub fn music_loop() -> Vec<f32> {
+    const BEAT: f32 = 0.25; // 八分音符，每分钟 120 拍
+    // 以半音表示的音高，相对 A3（220Hz）；None 为休止
+    const MELODY: [Option<i32>; 32] = [
+        Some(3), None, Some(10), Some(7), Some(8), Some(7), Some(5), Some(3),
+        Some(5), None, Some(12), Some(10), Some(8), Some(7), Some(5), None,
+        Some(3), None, Some(10), Some(7), Some(8), Some(10), Some(12), Some(15),
+        Some(14), Some(12), Some(10), Some(8), Some(7), None, Some(3), None,
+    ];
+    const BASS: [i32; 4] = [-9, -16, -13, -11];
+
+    let pitch = |semitones: i32| 220.0 * 2f32.powf(semitones as f32 / 12.0);
+    let melody: Vec<Note> = MELODY
+        .iter()
+        .map(|note| match note {
+            Some(semitones) => Note::new(Wave::Square, pitch(*semitones), BEAT, 0.08),
+            None => Note::new(Wave::Square, 0.0, BEAT, 0.0),
+        })
+        .collect();
+    let bass: Vec<Note> = BASS
+        .iter()
+        .flat_map(|&root| {
+            [root, root + 7, root + 12, root + 7]
+                .map(|semitones| Note::new(Wave::Triangle, pitch(semitones), BEAT * 2.0, 0.18))
+        })
+        .collect();
+    mix(render(&melody), &render(&bass))
+}
@@ -0,0 +1,256 @@
+// 主题：assets/themes/<名称>/ 下的一组贴图加一份 theme.txt 清单
+// 清单里缺少的条目或找不到的文件都会退回默认主题，所以一个主题可以只替换一部分内容
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use crate::sheet::SheetDescriptor;
+use crate::sim::CACTUS_CONFIGS;
+
+const THEME_HEADER: &str = "dino-theme v1";
+
+pub const THEME_DIR: &str = "themes";
+pub const DEFAULT_THEME: &str = "default";
+pub const MANIFEST_FILE: &str = "theme.txt";
+
+pub type Rgb = [f32; 3];
+
+// 清单原样解析的结果，图片路径相对于主题目录
+#[derive(Debug, Clone, Default)]
+pub struct ThemeManifest {
+    pub name: Option<String>,
+    pub dino_sheet: Option<String>, // 恐龙精灵表描述文件
+    pub obstacles: Vec<String>,     // 按 CACTUS_CONFIGS 的顺序，每种障碍物一张图
+    pub ground: Option<String>,
+    pub colors: HashMap<String, Rgb>, // 例如 day.sky、night.ground、hill
+    pub values: HashMap<String, f32>, // 例如 dust.count、debris.lifetime
+}
+
+impl ThemeManifest {
+    pub fn from_text(content: &str) -> io::Result<ThemeManifest> {
+        let mut lines = content.lines();
+        if lines.next().map(str::trim) != Some(THEME_HEADER) {
+            return Err(invalid("主题清单文件头不匹配"));
+        }
+
+        let mut manifest = ThemeManifest::default();
+        for line in lines {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let value = value.trim();
+            match key {
+                "name" => manifest.name = Some(value.to_string()),
+                "dino_sheet" => manifest.dino_sheet = Some(value.to_string()),
+                "obstacles" => manifest.obstacles = value.split_whitespace().map(str::to_string).collect(),
+                "ground" => manifest.ground = Some(value.to_string()),
+                _ => {
+                    // 其余条目是颜色（键 r g b）或数值（键 值）
+                    let numbers: Vec<f32> = value
+                        .split_whitespace()
+                        .map(|v| v.parse().map_err(invalid))
+                        .collect::<io::Result<_>>()?;
+                    match numbers[..] {
+                        [r, g, b] => {
+                            manifest.colors.insert(key.to_string(), [r, g, b]);
+                        }
+                        [number] => {
+                            manifest.values.insert(key.to_string(), number);
+                        }
+                        _ => return Err(invalid(format!("{} 需要一个数值或三个颜色分量", key))),
+                    }
+                }
+            }
+        }
+        Ok(manifest)
+    }
+}
+
+// 解析并补全后的主题，图片路径相对于 assets 目录，可以直接交给资源加载器
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub id: String,
+    pub name: String,
+    pub dino_sheet: SheetDescriptor, // 其中的图片路径已换算成相对于 assets 目录
+    pub obstacles: Vec<String>,      // 长度与 CACTUS_CONFIGS 相同
+    pub ground: String,
+    pub colors: HashMap<String, Rgb>,
+    pub values: HashMap<String, f32>,
+}
+
+impl Theme {
+    pub fn color(&self, key: &str) -> Option<Rgb> {
+        self.colors.get(key).copied()
+    }
+
+    pub fn value(&self, key: &str) -> Option<f32> {
+        self.values.get(key).copied()
+    }
+}
+
+fn theme_path(id: &str, file: &str) -> String {
+    format!("{}/{}/{}", THEME_DIR, id, file)
+}
+
+fn read_manifest(assets_root: &Path, id: &str) -> io::Result<ThemeManifest> {
+    let content = std::fs::read_to_string(assets_root.join(theme_path(id, MANIFEST_FILE)))?;
+    ThemeManifest::from_text(&content)
+}
+
+// 读取精灵表描述，并把图片路径换算成相对于 assets 目录
+fn read_sheet(assets_root: &Path, path: &str) -> io::Result<SheetDescriptor> {
+    let mut sheet = SheetDescriptor::from_text(&std::fs::read_to_string(assets_root.join(path))?)?;
+    let dir = path.rsplit_once('/').map_or("", |(dir, _)| dir);
+    sheet.image = format!("{}/{}", dir, sheet.image);
+    if !assets_root.join(&sheet.image).is_file() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("找不到 {}", sheet.image)));
+    }
+    Ok(sheet)
+}
+
+// 加载主题；主题不存在或清单损坏时直接使用默认主题，精灵表读取失败时使用默认精灵表
+// 退回默认值的原因一并返回，由调用方决定如何提示
+pub fn load_theme(assets_root: &Path, id: &str) -> (Theme, Vec<io::Error>) {
+    let mut errors = Vec::new();
+    let base = read_manifest(assets_root, DEFAULT_THEME).unwrap_or_default();
+    let manifest = if id == DEFAULT_THEME {
+        Ok(base.clone())
+    } else {
+        read_manifest(assets_root, id)
+    };
+    let (id, manifest) = match manifest {
+        Ok(manifest) => (id, manifest),
+        Err(e) => {
+            errors.push(context(e, format!("无法加载主题 {}，使用默认主题", id)));
+            (DEFAULT_THEME, base.clone())
+        }
+    };
+
+    // 主题里存在的文件用主题的，否则用默认主题的
+    let resolve = |own: Option<&String>, fallback: Option<&String>| -> String {
+        match own {
+            Some(file) if assets_root.join(theme_path(id, file)).is_file() => theme_path(id, file),
+            _ => theme_path(DEFAULT_THEME, fallback.map_or("", String::as_str)),
+        }
+    };
+
+    // 主题的精灵表读取失败时使用默认主题的
+    let default_sheet = |errors: &mut Vec<io::Error>| {
+        read_sheet(assets_root, &resolve(None, base.dino_sheet.as_ref())).unwrap_or_else(|e| {
+            errors.push(context(e, "无法加载默认精灵表".to_string()));
+            SheetDescriptor::default()
+        })
+    };
+    let dino_sheet = match &manifest.dino_sheet {
+        Some(file) if id != DEFAULT_THEME => match read_sheet(assets_root, &theme_path(id, file)) {
+            Ok(sheet) => sheet,
+            Err(e) => {
+                errors.push(context(e, format!("无法加载主题 {} 的精灵表，使用默认精灵表", id)));
+                default_sheet(&mut errors)
+            }
+        },
+        _ => default_sheet(&mut errors),
+    };
+
+    let mut colors = base.colors.clone();
+    colors.extend(manifest.colors.clone());
+    let mut values = base.values.clone();
+    values.extend(manifest.values.clone());
+
+    let theme = Theme {
+        id: id.to_string(),
+        name: manifest.name.clone().unwrap_or_else(|| id.to_string()),
+        dino_sheet,
+        obstacles: (0..CACTUS_CONFIGS.len())
+            .map(|i| resolve(manifest.obstacles.get(i), base.obstacles.get(i)))
+            .collect(),
+        ground: resolve(manifest.ground.as_ref(), base.ground.as_ref()),
+        colors,
+        values,
+    };
+    (theme, errors)
+}
+
+// 列出所有带清单的主题目录，默认主题排在最前
+pub fn list_themes(assets_root: &Path) -> Vec<String> {
+    let mut themes: Vec<String> = std::fs::read_dir(assets_root.join(THEME_DIR))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().join(MANIFEST_FILE).is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|id| id != DEFAULT_THEME)
+        .collect();
+    themes.sort();
+    themes.insert(0, DEFAULT_THEME.to_string());
+    themes
+}
+
+fn invalid(e: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+// 在错误信息前加上说明，保留原来的错误类型
+fn context(e: io::Error, message: String) -> io::Error {
+    io::Error::new(e.kind(), format!("{}: {}", message, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assets_root() -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("assets")
+    }
+
+    #[test]
+    fn parses_files_colors_and_values() {
+        let manifest = ThemeManifest::from_text(
+            "dino-theme v1\n# 注释\nname Test\nobstacles a.png b.png\nday.sky 0.1 0.2 0.3\ndust.count 6\n",
+        )
+        .unwrap();
+        assert_eq!(manifest.name.as_deref(), Some("Test"));
+        assert_eq!(manifest.obstacles, ["a.png", "b.png"]);
+        assert_eq!(manifest.colors["day.sky"], [0.1, 0.2, 0.3]);
+        assert_eq!(manifest.values["dust.count"], 6.0);
+        assert!(manifest.dino_sheet.is_none());
+    }
+
+    #[test]
+    fn rejects_wrong_header_and_malformed_entries() {
+        assert!(ThemeManifest::from_text("dino-theme v2\n").is_err());
+        assert!(ThemeManifest::from_text("dino-theme v1\nday.sky 0.1 0.2\n").is_err());
+        assert!(ThemeManifest::from_text("dino-theme v1\nhill red\n").is_err());
+    }
+
+    #[test]
+    fn bundled_themes_load_without_errors() {
+        let root = assets_root();
+        for id in list_themes(&root) {
+            let (theme, errors) = load_theme(&root, &id);
+            assert!(errors.is_empty(), "{}: {:?}", id, errors);
+            assert_eq!(theme.id, id);
+            assert_eq!(theme.obstacles.len(), CACTUS_CONFIGS.len());
+        }
+    }
+
+    #[test]
+    fn partial_theme_falls_back_to_default_files() {
+        let (theme, _) = load_theme(&assets_root(), "classic");
+        let (default, _) = load_theme(&assets_root(), DEFAULT_THEME);
+        assert_eq!(theme.ground, default.ground);
+        assert_eq!(theme.dino_sheet.image, default.dino_sheet.image);
+        assert_eq!(theme.color("day.sky"), Some([0.97, 0.97, 0.97]));
+        assert_eq!(theme.color("moon"), default.color("moon"));
+    }
+
+    #[test]
+    fn missing_theme_reports_error_and_uses_default() {
+        let (theme, errors) = load_theme(&assets_root(), "no-such-theme");
+        assert_eq!(theme.id, DEFAULT_THEME);
+        assert_eq!(errors.len(), 1);
+    }
+}